@@ -11,6 +11,26 @@ export function fragmentDecorate(f) {
 	fragmentDecorators.set(f, [f.firstChild, f.lastChild]);
 	return f.lastChild;
 }
+export function fragmentAdopt(b, e) {
+	let f = document.createDocumentFragment();
+	fragmentDecorators.set(f, [b, e]);
+	return f;
+}
+export function insertBefore(a,n) { let d = fragmentDecorators.get(a); (d ? d[0] : a).before(n); }
+export function moveBefore(a,n)
+{
+	let d = fragmentDecorators.get(a);
+	let r = d ? d[0] : a;
+	let p = r.parentNode;
+	let [b, e] = fragmentDecorators.get(n) || [n, n];
+	let m = p.moveBefore && r.isConnected ? p.moveBefore : p.insertBefore;
+	for (;;) {
+		let next = b.nextSibling;
+		m.call(p, b, r);
+		if (b === e) break;
+		b = next;
+	}
+}
 export function fragmentUnmount(f)
 {
 	let [b, e] = fragmentDecorators.get(f);
@@ -28,6 +48,16 @@ export function fragmentReplace(f,n)
 }
 
 export function setChecked(n,v) { if (n.checked !== v) n.checked = v; }
+export function setProp(n,k,v) { n[k] = v; }
+export function setStyle(n,k,v) { n.style.setProperty(k,v); }
+
+export function documentBody() { return document.body; }
+export function injectStyle(c)
+{
+	let s = document.createElement("style");
+	s.textContent = c;
+	document.head.append(s);
+}
 
 export function addClass(n,v) { n.classList.add(v); }
 export function removeClass(n,v) { n.classList.remove(v); }
@@ -35,6 +65,20 @@ export function replaceClass(n,o,v) { n.classList.replace(o,v); }
 export function toggleClass(n,c,v) { n.classList.toggle(c,v); }
 
 export function makeEventHandler(c,f) { return (e) => wasmBindings.koboldCallback(e,c,f); }
+export function addListener(n,e,l,m)
+{
+	if (m & 24) {
+		let h = l;
+		l = (e) => {
+			if (m & 8) e.preventDefault();
+			if (m & 16) e.stopPropagation();
+			h(e);
+		};
+	}
+	n.addEventListener(e, l, { capture: !!(m & 1), passive: !!(m & 2), once: !!(m & 4) });
+}
+export function removeListener(n,e,l) { n.removeEventListener(e, l); }
+export function dispatchCustomEvent(n,e,d) { return n.dispatchEvent(new CustomEvent(e, { detail: d, bubbles: true, cancelable: true })); }
 export function checkEventHandler() { if (typeof wasmBindings !== "object") console.error(
 `Missing \`wasmBindings\` in global scope.
 As of Kobold v0.10 and Trunk v0.17.16 you no longer need to export bindings manually, \
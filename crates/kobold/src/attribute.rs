@@ -3,25 +3,155 @@
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
 //! Utilities for dealing with DOM attributes
+//!
+//! ## Data and ARIA attributes
+//!
+//! The [`view!`](crate::view) macro sets `data-*` and `aria-*` attributes with `data:name`
+//! and `aria:name`. Values of those are diffed like any other attribute, and an `Option`
+//! that is `None` removes the attribute from the element:
+//!
+//! ```
+//! use kobold::prelude::*;
+//!
+//! #[component]
+//! fn tab(id: u32, selected: bool, label: Option<&str>) -> impl View + '_ {
+//!     view! {
+//!         <button data:tab-id={id} aria:selected={selected} aria:label={label}>
+//!             "Tab "{ id }
+//!         </button>
+//!     }
+//! }
+//! # fn main() {
+//! let html = kobold::render_to_string(view! {
+//!     <!tab id={2} selected={true} label={None}>
+//! });
+//!
+//! assert_eq!(html, r#"<button data-tab-id="2" aria-selected="true">Tab 2</button>"#);
+//! # }
+//! ```
+//!
+//! Event handlers can read them back from the event target, see
+//! [`EventTarget::data`](crate::event::EventTarget::data).
+//!
+//! ## Optional and boolean attributes
+//!
+//! Any attribute set by name, as well as `href`, `style` and `value`, can be given an `Option`,
+//! in which case `None` removes the attribute from the element (or clears the `value`).
+//! Boolean attributes such as `disabled`, `hidden`, `readonly`, `required` or `open` are
+//! present on the element when `true` and absent when `false`, instead of being set to
+//! the `"true"` or `"false"` strings. They also accept strings for keyword values, such as
+//! `hidden={"until-found"}`:
+//!
+//! ```
+//! use kobold::prelude::*;
+//!
+//! #[component]
+//! fn submit(busy: bool, hint: Option<&'static str>, help: Option<&'static str>) -> impl View {
+//!     view! {
+//!         <button type="submit" disabled={busy} title={hint}>"Send"</button>
+//!         <a href={help}>"Help"</a>
+//!     }
+//! }
+//! # fn main() {
+//! assert_eq!(
+//!     kobold::render_to_string(view! { <!submit busy={true} hint={None} help={None}> }),
+//!     r#"<button type="submit" disabled>Send</button><a>Help</a>"#,
+//! );
+//! assert_eq!(
+//!     kobold::render_to_string(view! {
+//!         <!submit busy={false} hint={Some("Ctrl+Enter")} help={Some("/help")}>
+//!     }),
+//!     r#"<button type="submit" title="Ctrl+Enter">Send</button><a href="/help">Help</a>"#,
+//! );
+//! # }
+//! ```
+//!
+//! The `checked`, `selected` and `muted` attributes only set the initial state of an element,
+//! so those are set through their DOM properties instead, which always reflect the current state.
+use std::marker::PhantomData;
 use std::ops::Deref;
 
 use web_sys::Node;
 
 use crate::diff::{Diff, Ref, VString};
-use crate::dom::Property;
+use crate::dom::{NodeRef, Property};
 use crate::internal;
+use crate::ssr::Html;
 use crate::value::Value as Text;
 
-/// Arbitrary attribute: <https://developer.mozilla.org/en-US/docs/Web/API/Element/setAttribute>
-pub struct AttributeName(str);
+macro_rules! attribute_name {
+    ($(#[doc = $doc:literal] $name:ident)*) => {
+        $(
+            #[doc = $doc]
+            pub struct $name(str);
+
+            impl From<&str> for &$name {
+                fn from(attr: &str) -> Self {
+                    unsafe { &*(attr as *const _ as *const $name) }
+                }
+            }
+
+            impl Deref for $name {
+                type Target = str;
+
+                fn deref(&self) -> &str {
+                    &self.0
+                }
+            }
+
+            impl Property<&str> for &$name {
+                fn set(self, this: &Node, value: &str) {
+                    internal::obj(this).set_attr(self, value);
+                }
+
+                fn render_html(self, html: &mut Html, value: &str) {
+                    html.attr(self, value);
+                }
+            }
+
+            impl Property<f64> for &$name {
+                fn set(self, this: &Node, value: f64) {
+                    internal::obj(this).set_attr_num(self, value)
+                }
+
+                fn render_html(self, html: &mut Html, value: f64) {
+                    html.attr(self, value);
+                }
+            }
+
+            impl Property<bool> for &$name {
+                fn set(self, this: &Node, value: bool) {
+                    internal::obj(this).set_attr_bool(self, value);
+                }
+
+                fn render_html(self, html: &mut Html, value: bool) {
+                    html.attr(self, value);
+                }
+            }
+        )*
+    };
+}
+
+attribute_name!(
+    /// Arbitrary attribute: <https://developer.mozilla.org/en-US/docs/Web/API/Element/setAttribute>
+    AttributeName
+    /// A `data-*` attribute, set with `data:name={value}`: <https://developer.mozilla.org/en-US/docs/Web/HTML/Global_attributes/data-*>
+    DataAttribute
+    /// An `aria-*` attribute, set with `aria:name={value}`: <https://developer.mozilla.org/en-US/docs/Web/Accessibility/ARIA/Attributes>
+    AriaAttribute
+);
+
+/// Boolean attribute such as `disabled` or `hidden`, which is either present on
+/// the element or absent: <https://developer.mozilla.org/en-US/docs/Glossary/Boolean/HTML>
+pub struct BooleanAttribute(str);
 
-impl From<&str> for &AttributeName {
+impl From<&str> for &BooleanAttribute {
     fn from(attr: &str) -> Self {
-        unsafe { &*(attr as *const _ as *const AttributeName) }
+        unsafe { &*(attr as *const _ as *const BooleanAttribute) }
     }
 }
 
-impl Deref for AttributeName {
+impl Deref for BooleanAttribute {
     type Target = str;
 
     fn deref(&self) -> &str {
@@ -29,26 +159,97 @@ impl Deref for AttributeName {
     }
 }
 
-impl Property<&str> for &AttributeName {
+impl Property<bool> for &BooleanAttribute {
+    fn set(self, this: &Node, value: bool) {
+        internal::obj(this).toggle_attr(self, value);
+    }
+
+    fn render_html(self, html: &mut Html, value: bool) {
+        if value {
+            html.flag(self);
+        }
+    }
+}
+
+/// Some boolean attributes also have keyword values, such as `hidden="until-found"`.
+impl Property<&str> for &BooleanAttribute {
     fn set(self, this: &Node, value: &str) {
         internal::obj(this).set_attr(self, value);
     }
+
+    fn render_html(self, html: &mut Html, value: &str) {
+        html.attr(self, value);
+    }
 }
 
-impl Property<f64> for &AttributeName {
-    fn set(self, this: &Node, value: f64) {
-        internal::obj(this).set_attr_num(self, value)
+/// A [`Property`] that can be removed from the element, such as when
+/// an `Option` attribute is `None`.
+pub trait RemoveAttribute {
+    fn remove(self, this: &Node);
+}
+
+macro_rules! remove_attribute {
+    ($($name:ident),*) => {
+        $(
+            impl RemoveAttribute for &$name {
+                fn remove(self, this: &Node) {
+                    internal::obj(this).remove_attr(self);
+                }
+            }
+        )*
+    };
+}
+
+remove_attribute!(
+    AttributeName,
+    DataAttribute,
+    AriaAttribute,
+    BooleanAttribute
+);
+
+/// A single CSS property set with `style:name={value}`:
+/// <https://developer.mozilla.org/en-US/docs/Web/API/CSSStyleDeclaration/setProperty>
+///
+/// Unlike the [`Style`] attribute, which replaces the whole inline style of an element,
+/// each property is diffed and updated separately, leaving other properties untouched.
+pub struct StyleProperty(str);
+
+impl From<&str> for &StyleProperty {
+    fn from(property: &str) -> Self {
+        unsafe { &*(property as *const _ as *const StyleProperty) }
     }
 }
 
-impl Property<bool> for &AttributeName {
-    fn set(self, this: &Node, value: bool) {
-        internal::obj(this).set_attr_bool(self, value);
+impl Deref for StyleProperty {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Property<&str> for &StyleProperty {
+    fn set(self, this: &Node, value: &str) {
+        internal::set_style(this, self, value);
+    }
+
+    fn render_html(self, html: &mut Html, value: &str) {
+        html.style(self, value);
+    }
+}
+
+impl Property<f64> for &StyleProperty {
+    fn set(self, this: &Node, value: f64) {
+        internal::set_style_num(this, self, value);
+    }
+
+    fn render_html(self, html: &mut Html, value: f64) {
+        html.style(self, value);
     }
 }
 
 macro_rules! attribute {
-    ($(#[doc = $doc:literal] $name:ident [ $($util:ident: $abi:ty),* ])*) => {
+    ($(#[doc = $doc:literal] $name:ident ($attr:literal) [ $($util:ident: $abi:ty),* ])*) => {
         $(
             #[doc = $doc]
             pub struct $name;
@@ -58,6 +259,10 @@ macro_rules! attribute {
                     fn set(self, this: &Node, value: $abi) {
                         internal::obj(this).$util(value);
                     }
+
+                    fn render_html(self, html: &mut Html, value: $abi) {
+                        html.attr($attr, value);
+                    }
                 }
             )*
         )*
@@ -71,6 +276,71 @@ impl Property<bool> for Checked {
     fn set(self, this: &Node, value: bool) {
         internal::checked(this, value);
     }
+
+    fn render_html(self, html: &mut Html, value: bool) {
+        if value {
+            html.flag("checked");
+        }
+    }
+}
+
+/// The `selected` attribute: <https://developer.mozilla.org/en-US/docs/Web/API/HTMLOptionElement/selected>
+pub struct Selected;
+
+impl Property<bool> for Selected {
+    fn set(self, this: &Node, value: bool) {
+        internal::set_prop(this, "selected", value);
+    }
+
+    fn render_html(self, html: &mut Html, value: bool) {
+        if value {
+            html.flag("selected");
+        }
+    }
+}
+
+/// The `muted` attribute: <https://developer.mozilla.org/en-US/docs/Web/API/HTMLMediaElement/muted>
+pub struct Muted;
+
+impl Property<bool> for Muted {
+    fn set(self, this: &Node, value: bool) {
+        internal::set_prop(this, "muted", value);
+    }
+
+    fn render_html(self, html: &mut Html, value: bool) {
+        if value {
+            html.flag("muted");
+        }
+    }
+}
+
+/// The `innerHTML` attribute: <https://developer.mozilla.org/en-US/docs/Web/API/Element/innerHTML>
+pub struct InnerHtml;
+
+impl Property<&str> for InnerHtml {
+    fn set(self, this: &Node, value: &str) {
+        internal::obj(this).inner_html(value);
+    }
+
+    /// Renders the value verbatim, it must be placed in the content of the element.
+    fn render_html(self, html: &mut Html, value: &str) {
+        html.raw(value);
+    }
+}
+
+/// The `ref` attribute binding a [`NodeRef`] to an element of type `T`.
+pub struct ElementRef<T>(PhantomData<T>);
+
+impl<T> ElementRef<T> {
+    pub const fn new() -> Self {
+        ElementRef(PhantomData)
+    }
+}
+
+impl<T> Default for ElementRef<T> {
+    fn default() -> Self {
+        ElementRef::new()
+    }
 }
 
 /// The `Element.classList` property: <https://developer.mozilla.org/en-US/docs/Web/API/Element/classList>
@@ -78,17 +348,34 @@ pub struct Class;
 
 attribute!(
     /// The `className` attribute: <https://developer.mozilla.org/en-US/docs/Web/API/Element/className>
-    ClassName [class_name: &str]
-    /// The `innerHTML` attribute: <https://developer.mozilla.org/en-US/docs/Web/API/Element/innerHTML>
-    InnerHtml [inner_html: &str]
+    ClassName ("class") [class_name: &str]
     /// The `style` attribute: <https://developer.mozilla.org/en-US/docs/Web/API/HTMLElement/style>
-    Style [style: &str]
+    Style ("style") [style: &str]
     /// The `href` attribute: <https://developer.mozilla.org/en-US/docs/Web/API/HTMLAnchorElement/href>
-    Href [href: &str]
+    Href ("href") [href: &str]
     /// The `value` attribute: <https://developer.mozilla.org/en-US/docs/Web/HTML/Element/input#value>
-    Value [value: &str, value_num: f64]
+    Value ("value") [value: &str, value_num: f64]
 );
 
+impl RemoveAttribute for Style {
+    fn remove(self, this: &Node) {
+        internal::obj(this).remove_attr("style");
+    }
+}
+
+impl RemoveAttribute for Href {
+    fn remove(self, this: &Node) {
+        internal::obj(this).remove_attr("href");
+    }
+}
+
+impl RemoveAttribute for Value {
+    /// Form fields keep their current value when the attribute is removed, so it's cleared instead.
+    fn remove(self, this: &Node) {
+        internal::obj(this).value("");
+    }
+}
+
 pub trait Attribute<P> {
     type Product: 'static;
 
@@ -97,6 +384,9 @@ pub trait Attribute<P> {
     fn build_in(self, prop: P, node: &Node) -> Self::Product;
 
     fn update_in(self, prop: P, node: &Node, memo: &mut Self::Product);
+
+    /// Render this attribute as HTML, see [`render_to_string`](crate::render_to_string).
+    fn render_html(self, prop: P, html: &mut Html);
 }
 
 impl<P> Attribute<P> for String
@@ -120,6 +410,10 @@ where
             *prod = self;
         }
     }
+
+    fn render_html(self, prop: P, html: &mut Html) {
+        prop.render_html(html, &self);
+    }
 }
 
 impl<P> Attribute<P> for bool
@@ -139,6 +433,10 @@ where
     fn update_in(self, prop: P, node: &Node, _: &mut ()) {
         self.set_prop(prop, node);
     }
+
+    fn render_html(self, prop: P, html: &mut Html) {
+        self.render_prop(prop, html);
+    }
 }
 
 macro_rules! impl_attribute_view {
@@ -164,6 +462,10 @@ macro_rules! impl_attribute_view {
                         self.set_prop(prop, node);
                     }
                 }
+
+                fn render_html(self, prop: P, html: &mut Html) {
+                    self.render_prop(prop, html);
+                }
             }
         )*
     };
@@ -172,6 +474,92 @@ macro_rules! impl_attribute_view {
 impl_attribute_view!(&str, &String, &Ref<str>, &VString);
 impl_attribute_view!(u8, u16, u32, u64, u128, usize, isize, i8, i16, i32, i64, i128, f32, f64);
 
+/// Attribute that is only present on the element when it's `Some`.
+impl<P, T> Attribute<P> for Option<T>
+where
+    P: RemoveAttribute,
+    T: Attribute<P>,
+{
+    type Product = Option<T::Product>;
+
+    fn build(self) -> Self::Product {
+        self.map(T::build)
+    }
+
+    fn build_in(self, prop: P, node: &Node) -> Self::Product {
+        self.map(|value| value.build_in(prop, node))
+    }
+
+    fn update_in(self, prop: P, node: &Node, memo: &mut Self::Product) {
+        match (self, memo) {
+            (Some(value), Some(memo)) => value.update_in(prop, node, memo),
+            (Some(value), memo) => *memo = Some(value.build_in(prop, node)),
+            (None, memo) => {
+                if memo.take().is_some() {
+                    prop.remove(node);
+                }
+            }
+        }
+    }
+
+    fn render_html(self, prop: P, html: &mut Html) {
+        if let Some(value) = self {
+            value.render_html(prop, html);
+        }
+    }
+}
+
+impl<T> Attribute<ElementRef<T>> for &NodeRef<T>
+where
+    T: 'static,
+{
+    type Product = NodeRef<T>;
+
+    fn build(self) -> NodeRef<T> {
+        self.clone()
+    }
+
+    fn build_in(self, _: ElementRef<T>, node: &Node) -> NodeRef<T> {
+        self.bind(node);
+        self.clone()
+    }
+
+    fn update_in(self, _: ElementRef<T>, node: &Node, memo: &mut NodeRef<T>) {
+        if !self.same(memo) {
+            self.bind(node);
+            *memo = self.clone();
+        }
+    }
+
+    /// References are only bound in the DOM
+    fn render_html(self, _: ElementRef<T>, _: &mut Html) {}
+}
+
+impl<T> Attribute<ElementRef<T>> for NodeRef<T>
+where
+    T: 'static,
+{
+    type Product = NodeRef<T>;
+
+    fn build(self) -> NodeRef<T> {
+        self
+    }
+
+    fn build_in(self, _: ElementRef<T>, node: &Node) -> NodeRef<T> {
+        self.bind(node);
+        self
+    }
+
+    fn update_in(self, _: ElementRef<T>, node: &Node, memo: &mut NodeRef<T>) {
+        if !self.same(memo) {
+            self.bind(node);
+            *memo = self;
+        }
+    }
+
+    fn render_html(self, _: ElementRef<T>, _: &mut Html) {}
+}
+
 #[inline]
 fn debug_test_class(class: &str) {
     debug_assert!(
@@ -218,6 +606,10 @@ where
             old.push_str(self.as_ref());
         }
     }
+
+    fn render_html(self, _: Class, html: &mut Html) {
+        html.class(self.as_ref());
+    }
 }
 
 impl Attribute<Class> for String {
@@ -238,20 +630,25 @@ impl Attribute<Class> for String {
             *old = self;
         }
     }
+
+    fn render_html(self, _: Class, html: &mut Html) {
+        html.class(&self);
+    }
 }
 
 #[derive(Clone, Copy)]
 pub struct StaticClass<T> {
+    class: &'static str,
     toggle: T,
     on: bool,
 }
 
 impl<T> StaticClass<T> {
-    pub const fn new(toggle: T, on: bool) -> Self
+    pub const fn new(class: &'static str, toggle: T, on: bool) -> Self
     where
         T: Fn(&Node, bool),
     {
-        StaticClass { toggle, on }
+        StaticClass { class, toggle, on }
     }
 }
 
@@ -276,6 +673,12 @@ where
             *memo = self.on;
         }
     }
+
+    fn render_html(self, _: Class, html: &mut Html) {
+        if self.on {
+            html.class(self.class);
+        }
+    }
 }
 
 impl<T> Attribute<ClassName> for StaticClass<T>
@@ -299,6 +702,12 @@ where
             *memo = self.on;
         }
     }
+
+    fn render_html(self, _: ClassName, html: &mut Html) {
+        if self.on {
+            html.attr("class", self.class);
+        }
+    }
 }
 
 #[derive(Clone, Copy)]
@@ -332,6 +741,12 @@ impl Attribute<Class> for OptionalClass {
             *memo = self.on;
         }
     }
+
+    fn render_html(self, _: Class, html: &mut Html) {
+        if self.on {
+            html.class(self.class);
+        }
+    }
 }
 
 impl Attribute<ClassName> for OptionalClass {
@@ -355,4 +770,40 @@ impl Attribute<ClassName> for OptionalClass {
             *memo = self.on;
         }
     }
+
+    fn render_html(self, _: ClassName, html: &mut Html) {
+        if self.on {
+            html.attr("class", self.class);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn render<T>(value: T, prop: &str) -> String
+    where
+        T: for<'a> Attribute<&'a BooleanAttribute>,
+    {
+        let mut html = Html::new();
+
+        value.render_html(prop.into(), &mut html);
+        html.into_string()
+    }
+
+    #[test]
+    fn boolean_attributes() {
+        assert_eq!(render(true, "hidden"), " hidden");
+        assert_eq!(render(false, "hidden"), "");
+        assert_eq!(render(Some(true), "open"), " open");
+        assert_eq!(render(Some(false), "open"), "");
+        assert_eq!(render(None::<bool>, "open"), "");
+        assert_eq!(render("until-found", "hidden"), r#" hidden="until-found""#);
+        assert_eq!(
+            render(Some("until-found"), "hidden"),
+            r#" hidden="until-found""#
+        );
+        assert_eq!(render(None::<&str>, "hidden"), "");
+    }
 }
@@ -0,0 +1,261 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! # Type-erased views
+//!
+//! Every [`view!`](crate::view) produces a unique type, so views chosen at runtime
+//! can't be stored in a single collection. [`BoxedView`] erases the type of any view,
+//! at the cost of an allocation and dynamic dispatch:
+//!
+//! ```
+//! use kobold::prelude::*;
+//! use kobold::boxed::BoxedView;
+//!
+//! enum Tile {
+//!     Clock,
+//!     Note(String),
+//! }
+//!
+//! fn tile(tile: &Tile) -> BoxedView {
+//!     match tile {
+//!         Tile::Clock => view! { <time>"12:00"</time> }.boxed(),
+//!         Tile::Note(text) => view! { <p.note>{ ref text }</p> }.boxed(),
+//!     }
+//! }
+//!
+//! #[component]
+//! fn dashboard(tiles: &[Tile]) -> impl View + '_ {
+//!     view! {
+//!         <div.dashboard>
+//!         { for tiles.iter().map(tile) }
+//!         </div>
+//!     }
+//! }
+//! # fn main() {}
+//! ```
+//!
+//! When a `BoxedView` is updated with a view of the same type as the one it was built
+//! from, the product is updated as usual. Otherwise the product is rebuilt and replaced
+//! in the DOM, the same way as switching variants of [`BranchN`](crate::branching) enums.
+//! Every `view!` has its own type, so switching between `Tile::Clock` and `Tile::Note`
+//! above always replaces the DOM, even if both views were static and had the same product.
+
+use std::any::{Any, TypeId};
+use std::marker::PhantomData;
+
+use wasm_bindgen::JsValue;
+use web_sys::Node;
+
+use crate::dom::Mountable;
+use crate::hydrate::Cursor;
+use crate::internal::{In, Out};
+use crate::ssr::Html;
+use crate::View;
+
+/// A [`View`] with its type erased, see the [module documentation](self).
+pub struct BoxedView<'a> {
+    view: Box<dyn DynView + 'a>,
+}
+
+impl<'a> BoxedView<'a> {
+    /// Erase the type of `view`, same as [`View::boxed`].
+    pub fn new(view: impl View + 'a) -> Self {
+        BoxedView {
+            view: Box::new(view),
+        }
+    }
+}
+
+/// Product of a [`BoxedView`].
+pub struct BoxedProduct {
+    product: Box<dyn DynProduct>,
+    /// Type of the view that built the `product`.
+    view: TypeId,
+}
+
+/// [`TypeId`] of any `T`, ignoring its lifetimes.
+fn type_id<T: ?Sized>() -> TypeId {
+    trait NonStaticAny {
+        fn type_id(&self) -> TypeId
+        where
+            Self: 'static;
+    }
+
+    impl<T: ?Sized> NonStaticAny for PhantomData<T> {
+        fn type_id(&self) -> TypeId
+        where
+            Self: 'static,
+        {
+            TypeId::of::<T>()
+        }
+    }
+
+    let phantom = PhantomData::<T>;
+
+    // ⚠️ Safety:
+    // ==========
+    //
+    // Lifetimes are erased before codegen, `TypeId` of `T` is the same for any lifetime.
+    NonStaticAny::type_id(unsafe {
+        std::mem::transmute::<&dyn NonStaticAny, &(dyn NonStaticAny + 'static)>(&phantom)
+    })
+}
+
+trait DynView {
+    fn view_id(&self) -> TypeId;
+
+    fn build_boxed(self: Box<Self>) -> BoxedProduct;
+
+    fn update_boxed(self: Box<Self>, p: &mut BoxedProduct);
+
+    fn hydrate_boxed(self: Box<Self>, c: &mut Cursor) -> BoxedProduct;
+
+    fn render_html_boxed(self: Box<Self>, html: &mut Html);
+}
+
+impl<V> DynView for V
+where
+    V: View,
+{
+    fn view_id(&self) -> TypeId {
+        type_id::<V>()
+    }
+
+    fn build_boxed(self: Box<Self>) -> BoxedProduct {
+        BoxedProduct {
+            view: self.view_id(),
+            product: In::boxed(move |p| View::build(*self, p)),
+        }
+    }
+
+    fn update_boxed(self: Box<Self>, p: &mut BoxedProduct) {
+        // Different views can share the same product type, such as `Node` for
+        // all static views, so comparing products alone is not enough.
+        if p.view == self.view_id() {
+            if let Some(p) = p.product.as_any_mut().downcast_mut::<V::Product>() {
+                return View::update(*self, p);
+            }
+        }
+
+        let new = self.build_boxed();
+
+        p.product.replace_with(new.product.js());
+        *p = new;
+    }
+
+    fn hydrate_boxed(self: Box<Self>, c: &mut Cursor) -> BoxedProduct {
+        BoxedProduct {
+            view: self.view_id(),
+            product: In::boxed(move |p| View::hydrate(*self, p, c)),
+        }
+    }
+
+    fn render_html_boxed(self: Box<Self>, html: &mut Html) {
+        View::render_html(*self, html)
+    }
+}
+
+trait DynProduct {
+    fn js(&self) -> &JsValue;
+
+    fn unmount(&self);
+
+    fn replace_with(&self, new: &JsValue);
+
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+impl<P> DynProduct for P
+where
+    P: Mountable,
+{
+    fn js(&self) -> &JsValue {
+        Mountable::js(self)
+    }
+
+    fn unmount(&self) {
+        Mountable::unmount(self)
+    }
+
+    fn replace_with(&self, new: &JsValue) {
+        Mountable::replace_with(self, new)
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+impl View for BoxedView<'_> {
+    type Product = BoxedProduct;
+
+    fn build(self, p: In<Self::Product>) -> Out<Self::Product> {
+        p.put(self.view.build_boxed())
+    }
+
+    fn update(self, p: &mut Self::Product) {
+        self.view.update_boxed(p);
+    }
+
+    fn hydrate<'p>(self, p: In<'p, Self::Product>, c: &mut Cursor) -> Out<'p, Self::Product> {
+        p.put(self.view.hydrate_boxed(c))
+    }
+
+    fn render_html(self, html: &mut Html) {
+        self.view.render_html_boxed(html);
+    }
+}
+
+impl Mountable for BoxedProduct {
+    type Js = Node;
+
+    fn js(&self) -> &JsValue {
+        self.product.js()
+    }
+
+    fn unmount(&self) {
+        self.product.unmount()
+    }
+
+    fn replace_with(&self, new: &JsValue) {
+        self.product.replace_with(new)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn render_boxed_views() {
+        let views: Vec<BoxedView> = vec!["a".boxed(), 42_u32.boxed(), Some("b").boxed()];
+
+        let html = crate::render_to_string(crate::keywords::r#for(views));
+
+        assert_eq!(html, "a42b");
+    }
+
+    #[test]
+    fn static_views_are_told_apart() {
+        use crate::internal::Precompiled;
+
+        let time: Box<dyn DynView> = Box::new(Precompiled(|| -> Node { unreachable!() }));
+        let note: Box<dyn DynView> = Box::new(Precompiled(|| -> Node { unreachable!() }));
+
+        // Both have `Node` for product, but updating one with the other must rebuild
+        assert_ne!(time.view_id(), note.view_id());
+        assert_eq!(time.view_id(), time.view_id());
+    }
+
+    #[test]
+    fn lifetimes_dont_change_view_id() {
+        fn borrowed<'a>(_: &'a str) -> TypeId {
+            type_id::<&'a str>()
+        }
+
+        let owned = String::from("text");
+
+        assert_eq!(borrowed(&owned), type_id::<&'static str>());
+    }
+}
@@ -76,6 +76,131 @@
 //!
 //! This is in fact all that the [`auto_branch`](crate::component#componentauto_branch) flag does for you automatically.
 //!
+//! ## Inline control flow
+//!
+//! Inside the [`view!`](crate::view) macro, `if` and `match` expressions can contain markup
+//! directly in their branches. Those are lowered to the `BranchN` enums the same way:
+//!
+//! ```
+//! # use kobold::prelude::*;
+//! enum Status {
+//!     Online,
+//!     Away(u32),
+//!     Offline,
+//! }
+//!
+//! #[component]
+//! fn user(name: &str, status: Status, admin: bool) -> impl View + '_ {
+//!     view! {
+//!         <div.user>
+//!             <b>{ ref name }</b>
+//!             { if admin { <span.badge>"Admin"</span> } }
+//!             {
+//!                 match status {
+//!                     Status::Online => <span.online>"Online"</span>,
+//!                     Status::Away(minutes) => {
+//!                         <span.away>"Away for "{ minutes }" minutes"</span>
+//!                     }
+//!                     Status::Offline => "Offline",
+//!                 }
+//!             }
+//!         </div>
+//!     }
+//! }
+//! # fn main() {
+//! # assert_eq!(
+//! #     kobold::render_to_string(view! { <!user name="Bob" status={Status::Away(5)} admin={true}> }),
+//! #     r#"<div class="user"><b>Bob</b><span class="badge">Admin</span><span class="away">Away for 5 minutes</span></div>"#,
+//! # );
+//! # assert_eq!(
+//! #     kobold::render_to_string(view! { <!user name="Alice" status={Status::Offline} admin={false}> }),
+//! #     r#"<div class="user"><b>Alice</b>Offline</div>"#,
+//! # );
+//! # }
+//! ```
+//!
+//! Branches that start with a tag are rendered with the `view!` macro, while other branches,
+//! such as the `"Offline"` text above, are regular Rust expressions. An `if` without an `else`
+//! renders nothing when the condition is false.
+//!
+//! Expressions without markup in any of their branches are left as they are:
+//!
+//! ```
+//! # use kobold::prelude::*;
+//! fn label<A, B>() -> &'static str {
+//!     "generic"
+//! }
+//!
+//! #[component]
+//! fn counts(n: u32, flag: bool) -> impl View {
+//!     view! {
+//!         <p>
+//!             { if flag { 1 } else { 2 } + 3 }
+//!             { match n { 0 => "zero", _ => "many" }.len() }
+//!         </p>
+//!         {
+//!             match n {
+//!                 0 => <b>"none"</b>,
+//!                 _ => label::<u8, u16>(),
+//!             }
+//!         }
+//!     }
+//! }
+//! # fn main() {
+//! # assert_eq!(kobold::render_to_string(view! { <!counts n={0} flag={true}> }), "<p>44</p><b>none</b>");
+//! # assert_eq!(kobold::render_to_string(view! { <!counts n={2} flag={false}> }), "<p>54</p>generic");
+//! # }
+//! ```
+//!
+//! A single `if` chain or `match` can have up to 9 branches, as many as the largest
+//! [`Branch9`] enum has variants:
+//!
+//! ```
+//! # use kobold::prelude::*;
+//! #[component]
+//! fn planet(n: u8) -> impl View {
+//!     view! {
+//!         <p>
+//!         {
+//!             match n {
+//!                 1 => <b>"Mercury"</b>,
+//!                 2 => <b>"Venus"</b>,
+//!                 3 => <b>"Earth"</b>,
+//!                 4 => <b>"Mars"</b>,
+//!                 5 => <b>"Jupiter"</b>,
+//!                 6 => <b>"Saturn"</b>,
+//!                 7 => <b>"Uranus"</b>,
+//!                 8 => <b>"Neptune"</b>,
+//!                 _ => <i>"Not a planet"</i>,
+//!             }
+//!         }
+//!         </p>
+//!     }
+//! }
+//! # fn main() {
+//! # assert_eq!(kobold::render_to_string(view! { <!planet n={3}> }), "<p><b>Earth</b></p>");
+//! # assert_eq!(kobold::render_to_string(view! { <!planet n={9}> }), "<p><i>Not a planet</i></p>");
+//! # }
+//! ```
+//!
+//! ```compile_fail
+//! # use kobold::prelude::*;
+//! #[component]
+//! fn too_many(n: u8) -> impl View {
+//!     view! {
+//!         <p>
+//!         {
+//!             match n {
+//!                 1 => <b>"1"</b>, 2 => <b>"2"</b>, 3 => <b>"3"</b>, 4 => <b>"4"</b>, 5 => <b>"5"</b>,
+//!                 6 => <b>"6"</b>, 7 => <b>"7"</b>, 8 => <b>"8"</b>, 9 => <b>"9"</b>, _ => <i>"?"</i>,
+//!             }
+//!         }
+//!         </p>
+//!     }
+//! }
+//! # fn main() {}
+//! ```
+//!
 //! For simple optional renders you can always use the standard library [`Option`]:
 //!
 //! ```
@@ -92,6 +217,28 @@
 //! }
 //! # fn main() {}
 //! ```
+//!
+//! ## Fallible components
+//!
+//! [`Result`] is also a view, as long as both the value and the error are views.
+//! This lets components return `Result<impl View, E>` and render errors explicitly
+//! instead of discarding them:
+//!
+//! ```
+//! # use kobold::prelude::*;
+//! #[component]
+//! fn parsed(input: &str) -> Result<impl View, String> {
+//!     let n: i32 = input.parse().map_err(|_| format!("Not a number: {input}"))?;
+//!
+//!     Ok(view! { <p>"Parsed "{ n }</p> })
+//! }
+//! # fn main() {
+//! # assert_eq!(kobold::render_to_string(view! { <!parsed input="42"> }), "<p>Parsed 42</p>");
+//! # assert_eq!(kobold::render_to_string(view! { <!parsed input="nope"> }), "Not a number: nope");
+//! # }
+//! ```
+//!
+//! To render an error view that doesn't match the type of the error, see [`error_boundary`](crate::error::error_boundary).
 
 use std::mem::MaybeUninit;
 use std::pin::Pin;
@@ -100,7 +247,9 @@ use wasm_bindgen::JsValue;
 use web_sys::Node;
 
 use crate::dom::Anchor;
+use crate::hydrate::Cursor;
 use crate::internal::{empty_node, In, Out};
+use crate::ssr::Html;
 use crate::{Mountable, View};
 
 macro_rules! branch {
@@ -156,6 +305,37 @@ macro_rules! branch {
                     }
                 }
             }
+
+            fn hydrate<'p>(self, p: In<'p, Self::Product>, c: &mut Cursor) -> Out<'p, Self::Product> {
+                let p: In<$name<$(MaybeUninit<$var::Product>),*>> = unsafe { p.cast() };
+
+                let out = match self {
+                    $(
+                        $name::$var(html) => {
+                            let mut p = p.put($name::$var(MaybeUninit::uninit()));
+
+                            match &mut *p {
+                                $name::$var(field) => {
+                                    In::pinned(unsafe { Pin::new_unchecked(field) }, move |p| html.hydrate(p, c));
+                                }
+                                _ => unsafe { std::hint::unreachable_unchecked() }
+                            }
+
+                            p
+                        },
+                    )*
+                };
+
+                unsafe { out.cast() }
+            }
+
+            fn render_html(self, html: &mut Html) {
+                match self {
+                    $(
+                        $name::$var(view) => view.render_html(html),
+                    )*
+                }
+            }
         }
 
         impl<$($var),*> Mountable for $name<$($var),*>
@@ -223,6 +403,12 @@ impl View for Empty {
     }
 
     fn update(self, _: &mut EmptyNode) {}
+
+    fn hydrate<'p>(self, p: In<'p, EmptyNode>, c: &mut Cursor) -> Out<'p, EmptyNode> {
+        p.put(EmptyNode(c.marker()))
+    }
+
+    fn render_html(self, _: &mut Html) {}
 }
 
 impl<T: View> View for Option<T> {
@@ -262,4 +448,67 @@ impl<T: View> View for Option<T> {
             }
         }
     }
+
+    fn hydrate<'p>(self, p: In<'p, Self::Product>, c: &mut Cursor) -> Out<'p, Self::Product> {
+        let p: In<Branch2<MaybeUninit<T::Product>, MaybeUninit<EmptyNode>>> = unsafe { p.cast() };
+
+        let out = match self {
+            Some(html) => {
+                let mut p = p.put(Branch2::A(MaybeUninit::uninit()));
+
+                match &mut *p {
+                    Branch2::A(field) => {
+                        In::pinned(unsafe { Pin::new_unchecked(field) }, move |p| {
+                            html.hydrate(p, c)
+                        });
+                    }
+                    Branch2::B(_) => unsafe { std::hint::unreachable_unchecked() },
+                }
+
+                p
+            }
+            None => p.put(Branch2::B(MaybeUninit::new(EmptyNode(c.marker())))),
+        };
+
+        unsafe { out.cast() }
+    }
+
+    fn render_html(self, html: &mut Html) {
+        if let Some(view) = self {
+            view.render_html(html);
+        }
+    }
+}
+
+impl<V, E> View for Result<V, E>
+where
+    V: View,
+    E: View,
+{
+    type Product = Branch2<V::Product, E::Product>;
+
+    fn build(self, p: In<Self::Product>) -> Out<Self::Product> {
+        Branch2::from(self).build(p)
+    }
+
+    fn update(self, p: &mut Self::Product) {
+        Branch2::from(self).update(p)
+    }
+
+    fn hydrate<'p>(self, p: In<'p, Self::Product>, c: &mut Cursor) -> Out<'p, Self::Product> {
+        Branch2::from(self).hydrate(p, c)
+    }
+
+    fn render_html(self, html: &mut Html) {
+        Branch2::from(self).render_html(html)
+    }
+}
+
+impl<A, B> From<Result<A, B>> for Branch2<A, B> {
+    fn from(result: Result<A, B>) -> Self {
+        match result {
+            Ok(a) => Branch2::A(a),
+            Err(b) => Branch2::B(b),
+        }
+    }
 }
@@ -0,0 +1,264 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! # Sharing values with nested components
+//!
+//! Instead of passing a value through the parameters of every component between
+//! the one that owns it and the one that needs it, the value can be [`provide`]d
+//! to the whole subtree of a view, where any component can access it with [`use_context`]:
+//!
+//! ```
+//! use kobold::prelude::*;
+//! use kobold::context::{provide, use_context};
+//!
+//! #[derive(Clone, Copy)]
+//! enum Theme {
+//!     Light,
+//!     Dark,
+//! }
+//!
+//! #[component]
+//! fn themed_button() -> impl View {
+//!     let class = match use_context::<Theme>() {
+//!         Some(Theme::Dark) => "dark",
+//!         _ => "light",
+//!     };
+//!
+//!     view! { <button.{class}>"Click me!"</button> }
+//! }
+//!
+//! #[component]
+//! fn app(theme: Theme) -> impl View {
+//!     provide(theme, || view! {
+//!         <div>
+//!             // No need to pass the theme around
+//!             <!themed_button>
+//!         </div>
+//!     })
+//! }
+//! # fn main() {}
+//! ```
+//!
+//! Providing a [`Signal`](crate::stateful::Signal) lets nested components
+//! change state they don't own. Components rendered by a [`stateful`](crate::stateful::stateful)
+//! view keep access to the context when the state updates them later.
+
+use std::any::Any;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::dom::Anchor;
+use crate::hydrate::Cursor;
+use crate::internal::{In, Out};
+use crate::ssr::Html;
+use crate::{init, Mountable, View};
+
+/// A single provided value, linked to all values provided above it.
+pub(crate) struct Context {
+    value: RefCell<Box<dyn Any>>,
+    parent: Option<Rc<Context>>,
+}
+
+impl Context {
+    /// Create a new context with `value`, linked to the current context.
+    pub(crate) fn new<T: 'static>(value: T) -> Rc<Self> {
+        Rc::new(Context {
+            value: RefCell::new(Box::new(value)),
+            parent: current(),
+        })
+    }
+}
+
+thread_local! {
+    static CURRENT: RefCell<Option<Rc<Context>>> = const { RefCell::new(None) };
+}
+
+/// Get the context of the view currently being rendered.
+pub(crate) fn current() -> Option<Rc<Context>> {
+    CURRENT.with(|current| current.borrow().clone())
+}
+
+/// Render views inside `f` with given context.
+pub(crate) fn scope<R>(context: Option<Rc<Context>>, f: impl FnOnce() -> R) -> R {
+    let _restore = Restore(CURRENT.with(|current| current.replace(context)));
+
+    f()
+}
+
+/// Restores the outer context when dropped, even if rendering panics.
+struct Restore(Option<Rc<Context>>);
+
+impl Drop for Restore {
+    fn drop(&mut self) {
+        let outer = self.0.take();
+
+        let _ = CURRENT.try_with(|current| *current.borrow_mut() = outer);
+    }
+}
+
+/// Get a clone of the nearest value of type `T` [`provide`]d by any of the parent views,
+/// or `None` if there isn't one.
+///
+/// This should be called while the view is being rendered, such as in the body of
+/// a component, or a render closure of [`stateful`](crate::stateful::stateful).
+pub fn use_context<T>() -> Option<T>
+where
+    T: Clone + 'static,
+{
+    let mut context = current();
+
+    while let Some(ctx) = context {
+        if let Some(value) = ctx.value.borrow().downcast_ref::<T>() {
+            return Some(value.clone());
+        }
+        context = ctx.parent.clone();
+    }
+
+    None
+}
+
+/// Make `value` accessible with [`use_context`] to all views created by the `render` closure,
+/// see the [module documentation](self).
+///
+/// When the parent view is updated with a new `value`, all views rendered afterwards
+/// will see the new value.
+pub fn provide<T, F, V>(value: T, render: F) -> Provide<T, F>
+where
+    T: 'static,
+    F: FnOnce() -> V,
+    V: View,
+{
+    Provide { value, render }
+}
+
+pub struct Provide<T, F> {
+    value: T,
+    render: F,
+}
+
+pub struct ProvideProduct<P> {
+    context: Rc<Context>,
+    product: P,
+}
+
+impl<T, F> Provide<T, F>
+where
+    T: 'static,
+{
+    fn context(self) -> (Rc<Context>, F) {
+        (Context::new(self.value), self.render)
+    }
+}
+
+impl<T, F, V> View for Provide<T, F>
+where
+    T: 'static,
+    F: FnOnce() -> V,
+    V: View,
+{
+    type Product = ProvideProduct<V::Product>;
+
+    fn build(self, p: In<Self::Product>) -> Out<Self::Product> {
+        let (context, render) = self.context();
+
+        p.in_place(|p| unsafe {
+            let ctx = init!(p.context = context).clone();
+
+            scope(Some(ctx), || init!(p.product @ render().build(p)));
+
+            Out::from_raw(p)
+        })
+    }
+
+    fn update(self, p: &mut Self::Product) {
+        {
+            let mut value = p.context.value.borrow_mut();
+
+            match value.downcast_mut::<T>() {
+                Some(value) => *value = self.value,
+                None => *value = Box::new(self.value),
+            }
+        }
+
+        scope(Some(p.context.clone()), || {
+            (self.render)().update(&mut p.product)
+        });
+    }
+
+    fn hydrate<'p>(self, p: In<'p, Self::Product>, c: &mut Cursor) -> Out<'p, Self::Product> {
+        let (context, render) = self.context();
+
+        p.in_place(|p| unsafe {
+            let ctx = init!(p.context = context).clone();
+
+            scope(Some(ctx), || init!(p.product @ render().hydrate(p, c)));
+
+            Out::from_raw(p)
+        })
+    }
+
+    fn render_html(self, html: &mut Html) {
+        let (context, render) = self.context();
+
+        scope(Some(context), || render().render_html(html));
+    }
+}
+
+impl<P> Anchor for ProvideProduct<P>
+where
+    P: Mountable,
+{
+    type Js = P::Js;
+    type Target = P;
+
+    fn anchor(&self) -> &P {
+        &self.product
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn nearest_value() {
+        let html = crate::render_to_string(provide(1_u32, || {
+            provide("outer", || {
+                provide(2_u32, || {
+                    assert_eq!(use_context::<u32>(), Some(2));
+                    assert_eq!(use_context::<&str>(), Some("outer"));
+                    assert_eq!(use_context::<bool>(), None);
+
+                    "inner"
+                })
+            })
+        }));
+
+        assert_eq!(html, "inner");
+        assert_eq!(use_context::<u32>(), None);
+    }
+
+    #[test]
+    fn restores_scope() {
+        let html = crate::render_to_string(provide(1_u32, || {
+            let inner = crate::render_to_string(provide(2_u32, use_context::<u32>));
+
+            assert_eq!(use_context::<u32>(), Some(1));
+
+            inner
+        }));
+
+        assert_eq!(html, "2");
+    }
+
+    #[test]
+    fn restores_scope_on_panic() {
+        let result = std::panic::catch_unwind(|| {
+            crate::render_to_string(provide(1_u32, || -> &str { panic!("render failed") }))
+        });
+
+        assert!(result.is_err());
+        assert_eq!(use_context::<u32>(), None);
+    }
+}
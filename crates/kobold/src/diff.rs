@@ -10,7 +10,9 @@ use web_sys::Node;
 
 use crate::attribute::Attribute;
 use crate::dom::{Anchor, TextContent};
+use crate::hydrate::Cursor;
 use crate::internal::{In, Out};
+use crate::ssr::Html;
 use crate::value::{IntoText, Value};
 use crate::{init, Mountable, View};
 
@@ -102,6 +104,14 @@ where
     }
 
     fn update(self, _: &mut Self::Product) {}
+
+    fn hydrate<'p>(self, p: In<'p, Self::Product>, c: &mut Cursor) -> Out<'p, Self::Product> {
+        (self.0)().hydrate(p, c)
+    }
+
+    fn render_html(self, html: &mut Html) {
+        (self.0)().render_html(html)
+    }
 }
 
 /// Smart [`View`] that guards against unnecessary renders, see [`fence`].
@@ -132,6 +142,19 @@ where
             (self.inner)().update(&mut p.inner);
         }
     }
+
+    fn hydrate<'p>(self, p: In<'p, Self::Product>, c: &mut Cursor) -> Out<'p, Self::Product> {
+        p.in_place(|p| unsafe {
+            init!(p.guard = self.guard.into_memo());
+            init!(p.inner @ (self.inner)().hydrate(p, c));
+
+            Out::from_raw(p)
+        })
+    }
+
+    fn render_html(self, html: &mut Html) {
+        (self.inner)().render_html(html)
+    }
 }
 
 impl<D, P> Anchor for Fence<D, P>
@@ -307,6 +330,14 @@ macro_rules! impl_no_diff {
                     self.0.set_prop(TextContent, node);
                 }
             }
+
+            fn hydrate<'p>(self, p: In<'p, Node>, c: &mut Cursor) -> Out<'p, Node> {
+                p.put(self.hydrate_text(c))
+            }
+
+            fn render_html(self, html: &mut Html) {
+                self.0.render_prop(TextContent, html);
+            }
         }
 
         impl<T, P> Attribute<P> for $name<T>
@@ -326,6 +357,10 @@ macro_rules! impl_no_diff {
                     self.0.set_prop(prop, node);
                 }
             }
+
+            fn render_html(self, prop: P, html: &mut Html) {
+                self.0.render_prop(prop, html);
+            }
         }
 
         impl<T> Diff for $name<T>
@@ -359,6 +394,18 @@ macro_rules! impl_no_diff {
                     self.0.set_prop(TextContent, p);
                 }
             }
+
+            fn hydrate<'p>(
+                self,
+                p: In<'p, Self::Product>,
+                c: &mut Cursor,
+            ) -> Out<'p, Self::Product> {
+                p.put(self.hydrate_text(c))
+            }
+
+            fn render_html(self, html: &mut Html) {
+                html.text(self.0);
+            }
         }
     };
 }
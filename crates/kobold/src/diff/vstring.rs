@@ -99,6 +99,9 @@ where
     }
 }
 
+// Can't be a `From<VString> for String` since it would conflict with
+// the blanket `From<S> for VString` implementation above.
+#[allow(clippy::from_over_into)]
 impl Into<String> for VString {
     fn into(self) -> String {
         self.inner
@@ -124,7 +127,7 @@ impl Eq for VString {}
 
 impl PartialOrd<VString> for VString {
     fn partial_cmp(&self, other: &VString) -> Option<std::cmp::Ordering> {
-        self.inner.partial_cmp(&other.inner)
+        Some(self.cmp(other))
     }
 }
 
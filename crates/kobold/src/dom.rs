@@ -4,12 +4,16 @@
 
 //! Utilities for mounting elements in the DOM
 
+use std::cell::RefCell;
+use std::marker::PhantomData;
 use std::ops::Deref;
+use std::rc::Rc;
 
 use wasm_bindgen::{JsCast, JsValue};
 use web_sys::Node;
 
 use crate::internal;
+use crate::ssr::Html;
 
 /// A type that can be mounted in the DOM
 pub trait Mountable: 'static {
@@ -27,6 +31,140 @@ pub trait Mountable: 'static {
     fn replace_with(&self, new: &JsValue);
 }
 
+/// Argument passed to the [`on_unmount`](crate::View::on_unmount) handler.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Unmount {
+    /// The product was detached from the DOM, it might be mounted again later.
+    Detached,
+    /// The product was dropped and won't be mounted again.
+    Dropped,
+}
+
+/// An element the app can be [mounted](crate::mount) into: either a CSS selector,
+/// or any DOM node.
+pub trait MountTarget {
+    /// Resolve the DOM node to mount into.
+    ///
+    /// # Panics
+    ///
+    /// When no element in the document matches the selector.
+    fn target(self) -> Node;
+}
+
+impl MountTarget for &str {
+    fn target(self) -> Node {
+        match internal::query_selector(self) {
+            Some(node) => node,
+            None => panic!("No element matching selector `{self}` to mount Kobold app into"),
+        }
+    }
+}
+
+impl<T> MountTarget for &T
+where
+    T: AsRef<Node>,
+{
+    fn target(self) -> Node {
+        self.as_ref().clone()
+    }
+}
+
+impl MountTarget for Node {
+    fn target(self) -> Node {
+        self
+    }
+}
+
+impl MountTarget for web_sys::Element {
+    fn target(self) -> Node {
+        self.into()
+    }
+}
+
+impl MountTarget for web_sys::HtmlElement {
+    fn target(self) -> Node {
+        self.into()
+    }
+}
+
+/// Reference to a DOM element rendered by the [`view!`](crate::view) macro, bound with
+/// the `ref` attribute and readable later, such as from event handlers or state updates.
+///
+/// The type `T` must match the element it's bound to, the same as the target type of
+/// [`Event`](crate::event::Event)s of that element.
+///
+/// ```
+/// use kobold::prelude::*;
+/// use web_sys::HtmlInputElement;
+///
+/// #[component]
+/// fn search() -> impl View {
+///     let input = NodeRef::<HtmlInputElement>::new();
+///     let focus = {
+///         let input = input.clone();
+///
+///         move |_| {
+///             if let Some(input) = input.get() {
+///                 let _ = input.focus();
+///             }
+///         }
+///     };
+///
+///     view! {
+///         <input type="search" ref={input}>
+///         <button onclick={focus}>"Search"</button>
+///     }
+/// }
+/// # fn main() {}
+/// ```
+pub struct NodeRef<T> {
+    node: Rc<RefCell<Option<Node>>>,
+    _marker: PhantomData<T>,
+}
+
+impl<T> NodeRef<T> {
+    /// Create a new `NodeRef` that isn't bound to any element yet.
+    pub fn new() -> Self {
+        NodeRef {
+            node: Rc::new(RefCell::new(None)),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Get the element this `NodeRef` is bound to, if any. The element might
+    /// no longer be mounted in the DOM.
+    pub fn get(&self) -> Option<T>
+    where
+        T: JsCast,
+    {
+        self.node.borrow().clone().map(JsCast::unchecked_into)
+    }
+
+    /// Check whether both `NodeRef`s point to the same binding.
+    pub fn same(&self, other: &NodeRef<T>) -> bool {
+        Rc::ptr_eq(&self.node, &other.node)
+    }
+
+    pub(crate) fn bind(&self, node: &Node) {
+        *self.node.borrow_mut() = Some(node.clone());
+    }
+}
+
+impl<T> Clone for NodeRef<T> {
+    fn clone(&self) -> Self {
+        NodeRef {
+            node: self.node.clone(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T> Default for NodeRef<T> {
+    fn default() -> Self {
+        NodeRef::new()
+    }
+}
+
 /// A light-weight [`Deref`]-like trait that
 /// auto-implements `Mountable` by proxying it to another type.
 pub trait Anchor {
@@ -73,6 +211,13 @@ impl From<Node> for Fragment {
     }
 }
 
+impl Fragment {
+    /// Adopt all nodes between `head` and `tail` already mounted in the DOM, inclusive.
+    pub(crate) fn adopt(head: &Node, tail: &Node) -> Self {
+        Fragment(internal::fragment_adopt(head, tail))
+    }
+}
+
 impl AsRef<JsValue> for Fragment {
     fn as_ref(&self) -> &JsValue {
         self.0.as_ref()
@@ -82,6 +227,9 @@ impl AsRef<JsValue> for Fragment {
 /// A settable property of a DOM `Node`
 pub trait Property<Abi> {
     fn set(self, this: &Node, value: Abi);
+
+    /// Render the value of this property as HTML.
+    fn render_html(self, html: &mut Html, value: Abi);
 }
 
 /// The `Node.textContent` property: <https://developer.mozilla.org/en-US/docs/Web/API/Node/textContent>
@@ -91,18 +239,30 @@ impl Property<&str> for TextContent {
     fn set(self, this: &Node, value: &str) {
         internal::obj(this).set_text(value);
     }
+
+    fn render_html(self, html: &mut Html, value: &str) {
+        html.text(value);
+    }
 }
 
 impl Property<f64> for TextContent {
     fn set(self, this: &Node, value: f64) {
         internal::obj(this).set_text_num(value);
     }
+
+    fn render_html(self, html: &mut Html, value: f64) {
+        html.text(value);
+    }
 }
 
 impl Property<bool> for TextContent {
     fn set(self, this: &Node, value: bool) {
         internal::obj(this).set_text_bool(value);
     }
+
+    fn render_html(self, html: &mut Html, value: bool) {
+        html.text(value);
+    }
 }
 
 pub(crate) struct FragmentBuilder {
@@ -117,9 +277,19 @@ impl FragmentBuilder {
         FragmentBuilder { fragment, tail }
     }
 
+    pub fn adopt(head: &Node, tail: Node) -> Self {
+        let fragment = Fragment::adopt(head, &tail);
+        FragmentBuilder { fragment, tail }
+    }
+
     pub fn append(&self, child: &JsValue) {
         internal::obj(&self.tail).append_before(child);
     }
+
+    /// Move already mounted `child` to the end of the fragment, keeping its nodes live.
+    pub fn move_to_end(&self, child: &JsValue) {
+        internal::move_before(&self.tail, child);
+    }
 }
 
 impl Deref for FragmentBuilder {
@@ -0,0 +1,212 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Side effects tied to the lifecycle of a view, see [`View::effect`].
+
+use crate::diff::Diff;
+use crate::dom::Anchor;
+use crate::hydrate::Cursor;
+use crate::internal::{In, Out};
+use crate::ssr::Html;
+use crate::{init, Mountable, View};
+
+/// Value returned from an effect, cleaned up before the effect runs
+/// again, or when the product of the view is dropped.
+pub trait Cleanup: 'static {
+    fn cleanup(self);
+}
+
+/// Effects with nothing to clean up.
+impl Cleanup for () {
+    fn cleanup(self) {}
+}
+
+/// Closures are called on cleanup.
+impl<F> Cleanup for F
+where
+    F: FnOnce() + 'static,
+{
+    fn cleanup(self) {
+        self()
+    }
+}
+
+impl<C> Cleanup for Option<C>
+where
+    C: Cleanup,
+{
+    fn cleanup(self) {
+        if let Some(cleanup) = self {
+            cleanup.cleanup();
+        }
+    }
+}
+
+/// Keep a value alive until cleanup, and then drop it. This can be used
+/// to return types that implement [`Drop`] from an effect:
+///
+/// ```
+/// use kobold::prelude::*;
+/// use kobold::effect::Keep;
+///
+/// struct Connection(u32);
+///
+/// impl Drop for Connection {
+///     fn drop(&mut self) {
+///         // close the connection...
+///     }
+/// }
+///
+/// #[component]
+/// fn channel(id: u32) -> impl View {
+///     view! { <p>"Channel "{ id }</p> }.effect(id, |id| Keep(Connection(id)))
+/// }
+/// # fn main() {}
+/// ```
+pub struct Keep<T>(pub T);
+
+impl<T: 'static> Cleanup for Keep<T> {
+    fn cleanup(self) {}
+}
+
+/// Smart [`View`] running a side effect whenever its dependencies change, see [`View::effect`].
+pub struct Effect<V, D, F> {
+    pub(crate) view: V,
+    pub(crate) deps: D,
+    pub(crate) effect: F,
+}
+
+pub struct EffectProduct<P, M, C: Cleanup> {
+    product: P,
+    memo: M,
+    cleanup: Option<C>,
+}
+
+impl<V, D, F, C> View for Effect<V, D, F>
+where
+    V: View,
+    D: Diff,
+    F: FnOnce(D) -> C,
+    C: Cleanup,
+{
+    type Product = EffectProduct<V::Product, D::Memo, C>;
+
+    fn build(self, p: In<Self::Product>) -> Out<Self::Product> {
+        p.in_place(|p| unsafe {
+            init!(p.product @ self.view.build(p));
+            init!(p.memo = self.deps.into_memo());
+            init!(p.cleanup = Some((self.effect)(self.deps)));
+
+            Out::from_raw(p)
+        })
+    }
+
+    fn update(self, p: &mut Self::Product) {
+        self.view.update(&mut p.product);
+        p.rerun(self.deps, self.effect);
+    }
+
+    fn hydrate<'p>(self, p: In<'p, Self::Product>, c: &mut Cursor) -> Out<'p, Self::Product> {
+        p.in_place(|p| unsafe {
+            init!(p.product @ self.view.hydrate(p, c));
+            init!(p.memo = self.deps.into_memo());
+            init!(p.cleanup = Some((self.effect)(self.deps)));
+
+            Out::from_raw(p)
+        })
+    }
+
+    fn render_html(self, html: &mut Html) {
+        // Effects only run in the browser
+        self.view.render_html(html);
+    }
+}
+
+impl<P, M, C> EffectProduct<P, M, C>
+where
+    C: Cleanup,
+{
+    fn rerun<D, F>(&mut self, deps: D, effect: F)
+    where
+        D: Diff<Memo = M>,
+        F: FnOnce(D) -> C,
+    {
+        if deps.diff(&mut self.memo) {
+            self.cleanup.take().cleanup();
+            self.cleanup = Some(effect(deps));
+        }
+    }
+}
+
+impl<P, M, C> Drop for EffectProduct<P, M, C>
+where
+    C: Cleanup,
+{
+    fn drop(&mut self) {
+        self.cleanup.take().cleanup();
+    }
+}
+
+impl<P, M, C> Anchor for EffectProduct<P, M, C>
+where
+    P: Mountable,
+    M: 'static,
+    C: Cleanup,
+{
+    type Js = P::Js;
+    type Target = P;
+
+    fn anchor(&self) -> &P {
+        &self.product
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use super::*;
+
+    #[test]
+    fn cleanup_before_next_effect_and_on_drop() {
+        let log = Rc::new(RefCell::new(Vec::new()));
+
+        let effect = |id: u32| {
+            let log = log.clone();
+
+            move |_| {
+                log.borrow_mut().push(format!("run {id}"));
+
+                let log = log.clone();
+
+                move || log.borrow_mut().push(format!("cleanup {id}"))
+            }
+        };
+
+        let mut product = EffectProduct {
+            product: (),
+            memo: 1_u32,
+            cleanup: Some(effect(1)(1)),
+        };
+
+        for id in [1, 2, 2, 3] {
+            product.rerun(id, effect(id));
+        }
+
+        drop(product);
+
+        assert_eq!(
+            *log.borrow(),
+            [
+                "run 1",
+                "cleanup 1",
+                "run 2",
+                "cleanup 2",
+                "run 3",
+                "cleanup 3"
+            ]
+        );
+    }
+}
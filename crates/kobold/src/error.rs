@@ -0,0 +1,416 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! # Containing failures in a part of the view
+//!
+//! Panics can't be recovered from in WASM, so instead of unwrapping errors inside
+//! a component, its render function can return a [`Result`] and let the nearest
+//! [`error_boundary`] render a fallback view in its place:
+//!
+//! ```
+//! use kobold::prelude::*;
+//! use kobold::error::error_boundary;
+//!
+//! struct ParseError(String);
+//!
+//! #[component]
+//! fn number(input: &str) -> impl View + '_ {
+//!     error_boundary(
+//!         move || {
+//!             let n: i32 = input.parse().map_err(|_| ParseError(input.into()))?;
+//!
+//!             Ok(view! { <p>"Parsed "{ n }</p> })
+//!         },
+//!         |ParseError(input)| view! { <p.error>"Not a number: "{ input }</p> },
+//!     )
+//! }
+//! # fn main() {}
+//! ```
+//!
+//! Nested components that don't return a `Result` can still [`throw`] errors
+//! to the boundary while it's rendering.
+
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+use crate::branching::Branch2;
+use crate::context::{scope, use_context, Context};
+use crate::dom::Anchor;
+use crate::hydrate::Cursor;
+use crate::internal::{In, Out};
+use crate::ssr::Html;
+use crate::{init, Mountable, View};
+
+/// Create a [`View`] rendering the result of `render`, or the `fallback` view
+/// if it fails, see the [module documentation](self).
+///
+/// The boundary catches errors returned from `render`, as well as errors of the same
+/// type [`throw`]n by any view rendered inside it while the boundary is built or updated.
+/// Once the boundary is updated and `render` succeeds again, the fallback is replaced
+/// with the rendered view.
+///
+/// Errors thrown by the `fallback` view propagate to the outer boundary.
+pub fn error_boundary<F, V, E, G, W>(render: F, fallback: G) -> ErrorBoundary<F, G>
+where
+    F: FnOnce() -> Result<V, E>,
+    V: View,
+    E: 'static,
+    G: FnOnce(E) -> W,
+    W: View,
+{
+    ErrorBoundary { render, fallback }
+}
+
+/// Throw an `error` to the nearest [`error_boundary`] catching errors of type `E`.
+///
+/// Errors can only be thrown while the boundary is rendering, such as in the body
+/// of a component. If there is no such boundary, the error is returned back.
+///
+/// ```
+/// use kobold::prelude::*;
+/// use kobold::error::{error_boundary, throw};
+///
+/// struct Missing(&'static str);
+///
+/// #[component]
+/// fn greeting(name: Option<&'static str>) -> impl View {
+///     if name.is_none() {
+///         let _ = throw(Missing("name"));
+///     }
+///
+///     view! { <p>"Hello, "{ static name.unwrap_or_default() }</p> }
+/// }
+///
+/// # fn main() {
+/// let html = kobold::render_to_string(error_boundary(
+///     || Ok(view! { <!greeting name={None}> }),
+///     |Missing(what)| view! { <p>"Missing "{ static what }</p> },
+/// ));
+///
+/// assert_eq!(html, "<p>Missing name</p>");
+/// # }
+/// ```
+pub fn throw<E>(error: E) -> Result<(), E>
+where
+    E: 'static,
+{
+    match use_context::<Catch<E>>() {
+        Some(catch) if catch.inner.active.get() => {
+            let mut slot = catch.inner.error.borrow_mut();
+
+            // Only the first error is kept
+            if slot.is_none() {
+                *slot = Some(error);
+            }
+            Ok(())
+        }
+        _ => Err(error),
+    }
+}
+
+struct CatchInner<E> {
+    error: RefCell<Option<E>>,
+    active: Cell<bool>,
+}
+
+/// Slot for errors thrown inside of a boundary, provided as context.
+struct Catch<E> {
+    inner: Rc<CatchInner<E>>,
+}
+
+impl<E> Clone for Catch<E> {
+    fn clone(&self) -> Self {
+        Catch {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<E: 'static> Catch<E> {
+    fn new() -> Self {
+        Catch {
+            inner: Rc::new(CatchInner {
+                error: RefCell::new(None),
+                active: Cell::new(false),
+            }),
+        }
+    }
+
+    /// Run `f` in `context`, catching errors thrown inside of it.
+    fn run<R>(&self, context: &Rc<Context>, f: impl FnOnce() -> R) -> R {
+        self.inner.active.set(true);
+
+        let _deactivate = Deactivate(&self.inner.active);
+
+        scope(Some(context.clone()), f)
+    }
+
+    fn take(&self) -> Option<E> {
+        self.inner.error.borrow_mut().take()
+    }
+}
+
+/// Stops catching errors once dropped, even if rendering panics.
+struct Deactivate<'a>(&'a Cell<bool>);
+
+impl Drop for Deactivate<'_> {
+    fn drop(&mut self) {
+        self.0.set(false);
+    }
+}
+
+pub struct ErrorBoundary<F, G> {
+    render: F,
+    fallback: G,
+}
+
+pub struct ErrorBoundaryProduct<P, Q, E> {
+    catch: Catch<E>,
+    context: Rc<Context>,
+    product: Branch2<P, Q>,
+}
+
+impl<F, V, E, G, W> View for ErrorBoundary<F, G>
+where
+    F: FnOnce() -> Result<V, E>,
+    V: View,
+    E: 'static,
+    G: FnOnce(E) -> W,
+    W: View,
+{
+    type Product = ErrorBoundaryProduct<V::Product, W::Product, E>;
+
+    fn build(self, p: In<Self::Product>) -> Out<Self::Product> {
+        let ErrorBoundary { render, fallback } = self;
+
+        p.in_place(|p| unsafe {
+            let catch = init!(p.catch = Catch::new()).clone();
+            let context = init!(p.context = Context::new(catch.clone())).clone();
+
+            let result = catch.run(&context, || {
+                let view = render()?;
+
+                init!(p.product @ Branch2::<V, W>::A(view).build(p));
+
+                Ok(())
+            });
+
+            match (result, catch.take()) {
+                (Ok(()), None) => (),
+                (Ok(()), Some(err)) => {
+                    In::replace(&mut (*p).product, |p| {
+                        Branch2::<V, W>::B(fallback(err)).build(p)
+                    });
+                }
+                (Err(err), _) => {
+                    init!(p.product @ Branch2::<V, W>::B(fallback(err)).build(p));
+                }
+            }
+
+            Out::from_raw(p)
+        })
+    }
+
+    fn update(self, p: &mut Self::Product) {
+        let ErrorBoundary { render, fallback } = self;
+        let ErrorBoundaryProduct {
+            catch,
+            context,
+            product,
+        } = p;
+
+        let result = catch.run(context, || {
+            let view = render()?;
+
+            Branch2::<V, W>::A(view).update(product);
+
+            Ok(())
+        });
+
+        if let Some(err) = result.err().or_else(|| catch.take()) {
+            Branch2::<V, W>::B(fallback(err)).update(product);
+        }
+    }
+
+    fn hydrate<'p>(self, p: In<'p, Self::Product>, c: &mut Cursor) -> Out<'p, Self::Product> {
+        let ErrorBoundary { render, fallback } = self;
+
+        p.in_place(|p| unsafe {
+            let catch = init!(p.catch = Catch::new()).clone();
+            let context = init!(p.context = Context::new(catch.clone())).clone();
+
+            let result = catch.run(&context, || {
+                let view = render()?;
+
+                // The server has rendered the fallback for errors thrown
+                // while rendering, so that's what needs to be hydrated
+                if let Some(err) = catch.take() {
+                    return Err(err);
+                }
+
+                let c = &mut *c;
+
+                init!(p.product @ Branch2::<V, W>::A(view).hydrate(p, c));
+
+                Ok(())
+            });
+
+            match (result, catch.take()) {
+                (Ok(()), None) => (),
+                // Thrown while hydrating, the nodes of the fallback rendered by the
+                // server have already been consumed and need to be replaced.
+                (Ok(()), Some(err)) => Branch2::<V, W>::B(fallback(err)).update(&mut (*p).product),
+                (Err(err), _) => {
+                    init!(p.product @ Branch2::<V, W>::B(fallback(err)).hydrate(p, c));
+                }
+            }
+
+            Out::from_raw(p)
+        })
+    }
+
+    fn render_html(self, html: &mut Html) {
+        let catch = Catch::new();
+        let context = Context::new(catch.clone());
+        let mut buf = Html::new();
+
+        let result = catch.run(&context, || render_ok(self.render, &mut buf));
+
+        match result.err().or_else(|| catch.take()) {
+            None => html.raw(buf.as_ref()),
+            Some(err) => (self.fallback)(err).render_html(html),
+        }
+    }
+}
+
+fn render_ok<V, E>(render: impl FnOnce() -> Result<V, E>, html: &mut Html) -> Result<(), E>
+where
+    V: View,
+{
+    render()?.render_html(html);
+
+    Ok(())
+}
+
+impl<P, Q, E> Anchor for ErrorBoundaryProduct<P, Q, E>
+where
+    P: Mountable,
+    Q: Mountable,
+    E: 'static,
+{
+    type Js = <Branch2<P, Q> as Mountable>::Js;
+    type Target = Branch2<P, Q>;
+
+    fn anchor(&self) -> &Self::Target {
+        &self.product
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn boundary(input: &str) -> String {
+        crate::render_to_string(error_boundary(
+            || {
+                let n: u32 = input.parse().map_err(|_| "parse")?;
+
+                if n == 0 {
+                    let _ = throw("zero");
+                }
+
+                Ok(n)
+            },
+            |err: &'static str| err,
+        ))
+    }
+
+    #[test]
+    fn catch_returned_and_thrown_errors() {
+        assert_eq!(boundary("42"), "42");
+        assert_eq!(boundary("nope"), "parse");
+        assert_eq!(boundary("0"), "zero");
+    }
+
+    #[test]
+    fn throw_outside_of_boundary() {
+        assert_eq!(throw(1_u32), Err(1));
+
+        let html = crate::render_to_string(error_boundary(
+            || Err::<&str, _>(1_u32),
+            |_| match throw(2_u32) {
+                Ok(()) => "caught",
+                Err(_) => "propagated",
+            },
+        ));
+
+        assert_eq!(html, "propagated");
+    }
+
+    #[test]
+    fn stop_catching_after_panic() {
+        use std::panic::AssertUnwindSafe;
+
+        let catch = Catch::<u32>::new();
+        let context = Context::new(catch.clone());
+
+        let result = std::panic::catch_unwind(AssertUnwindSafe(|| {
+            catch.run(&context, || {
+                assert_eq!(throw(1_u32), Ok(()));
+
+                panic!("render failed");
+            })
+        }));
+
+        assert!(result.is_err());
+        assert_eq!(throw(2_u32), Err(2));
+        assert_eq!(catch.take(), Some(1));
+    }
+
+    /// Run with `wasm-pack test --headless --chrome`
+    #[cfg(target_arch = "wasm32")]
+    mod browser {
+        use wasm_bindgen_test::{wasm_bindgen_test, wasm_bindgen_test_configure};
+        use web_sys::Node;
+
+        use super::*;
+
+        wasm_bindgen_test_configure!(run_in_browser);
+
+        fn thrown(n: u32) -> impl View {
+            error_boundary(
+                move || {
+                    if n == 0 {
+                        let _ = throw("zero");
+                    }
+
+                    Ok(n)
+                },
+                |err: &'static str| err,
+            )
+        }
+
+        #[wasm_bindgen_test]
+        fn hydrate_thrown_error() {
+            let document = web_sys::window().unwrap().document().unwrap();
+            let parent: Node = document.create_element("div").unwrap().into();
+
+            parent.set_text_content(Some(&crate::render_to_string(thrown(0))));
+
+            let server = parent.first_child().unwrap();
+            let mut cursor = Cursor::new(parent.clone());
+            let mut product = In::boxed(|p| thrown(0).hydrate(p, &mut cursor));
+
+            cursor.finish();
+
+            // Nodes rendered by the server are adopted by the fallback
+            assert_eq!(parent.text_content().unwrap(), "zero");
+            assert!(parent.first_child().unwrap().is_same_node(Some(&server)));
+
+            thrown(42).update(&mut product);
+
+            assert_eq!(parent.text_content().unwrap(), "42");
+        }
+    }
+}
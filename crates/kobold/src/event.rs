@@ -3,15 +3,184 @@
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
 //! Utilities for handling DOM events
+//!
+//! ## Listener modifiers
+//!
+//! Event listeners in the [`view!`](crate::view) macro accept modifiers after the event name:
+//!
+//! * `.capture`, `.passive` and `.once` set the [options](https://developer.mozilla.org/en-US/docs/Web/API/EventTarget/addEventListener#options)
+//!   of the listener.
+//! * `.prevent` calls [`prevent_default`](web_sys::Event::prevent_default) before the handler.
+//! * `.stop` calls [`stop_propagation`](web_sys::Event::stop_propagation) before the handler.
+//!
+//! ```
+//! use kobold::prelude::*;
+//!
+//! #[component]
+//! fn form() -> impl View {
+//!     view! {
+//!         <form onsubmit.prevent={|_| { /* submit via fetch */ }}>
+//!             <div onscroll.passive={|_| { /* sync scroll position */ }}>
+//!                 <button onclick.stop.once={|_| { /* clicked first time */ }}>"Submit"</button>
+//!             </div>
+//!         </form>
+//!     }
+//! }
+//! # fn main() {}
+//! ```
+//!
+//! ## Event types
+//!
+//! The type of the event a listener receives is inferred from the event name:
+//!
+//! * [`MouseEvent`] for `onclick`, `ondblclick`, `onmousedown`, `onmouseup`, `onmouseover`,
+//!   `onmousemove`, `onmouseout`, `onmouseenter`, `onmouseleave`
+//! * [`KeyboardEvent`] for `onkeydown`, `onkeyup`, `onkeypress`
+//! * [`InputEvent`] for `oninput`, `onbeforeinput`
+//! * [`FocusEvent`] for `onfocus`, `onblur`, `onfocusin`, `onfocusout`
+//! * [`PointerEvent`] for `onpointerdown`, `onpointerup`, `onpointermove`, `onpointerover`,
+//!   `onpointerout`, `onpointerenter`, `onpointerleave`, `onpointercancel`,
+//!   `ongotpointercapture`, `onlostpointercapture`
+//! * [`WheelEvent`] for `onwheel`
+//! * [`DragEvent`] for `ondrag`, `ondragstart`, `ondragend`, `ondragenter`, `ondragleave`,
+//!   `ondragover`, `ondrop`
+//! * [`TouchEvent`] for `ontouchstart`, `ontouchend`, `ontouchmove`, `ontouchcancel`
+//! * [`SubmitEvent`] for `onsubmit`
+//! * [`AnimationEvent`] for `onanimationstart`, `onanimationend`, `onanimationiteration`,
+//!   `onanimationcancel`
+//! * [`TransitionEvent`] for `ontransitionstart`, `ontransitionend`, `ontransitionrun`,
+//!   `ontransitioncancel`
+//! * [`ClipboardEvent`] for `oncopy`, `oncut`, `onpaste`
+//! * [`Event`] for everything else, such as `onchange` or `onscroll`
+//!
+//! Handlers defined outside of the `view!` macro need to accept the matching type:
+//!
+//! ```
+//! use kobold::prelude::*;
+//! use kobold::event::{FocusEvent, InputEvent, PointerEvent, SubmitEvent, WheelEvent};
+//! use kobold::reexport::web_sys::{HtmlElement, HtmlFormElement, HtmlInputElement};
+//!
+//! fn typed(_: InputEvent<HtmlInputElement>) {}
+//! fn left(_: FocusEvent<HtmlInputElement>) {}
+//! fn changed(_: Event<HtmlInputElement>) {}
+//! fn pressed(_: PointerEvent<HtmlElement>) {}
+//! fn scrolled(_: WheelEvent<HtmlElement>) {}
+//! fn submitted(_: SubmitEvent<HtmlFormElement>) {}
+//!
+//! #[component]
+//! fn form() -> impl View {
+//!     view! {
+//!         <form onsubmit={submitted}>
+//!             <input oninput={typed} onblur={left} onchange={changed}>
+//!             <div onpointerdown={pressed} onwheel={scrolled}>
+//!         </form>
+//!     }
+//! }
+//! # fn main() {}
+//! ```
+//!
+//! Annotating the argument of an inline closure, such as `|e: Event<HtmlInputElement>|`,
+//! overrides the inferred type.
+//!
+//! ## Custom events
+//!
+//! Any event name can be listened to, including names with dashes, such as `onitem-selected`.
+//! Listeners of events not known to **Kobold** receive an [`Event`] by default. Annotating
+//! the argument of an inline closure changes the type of the event it receives, which is
+//! how typed `CustomEvent`s are listened to. The `detail` of those is deserialized
+//! with [`serde`](https://serde.rs/), so they require the `serde` feature:
+//!
+#![cfg_attr(feature = "serde", doc = "```")]
+#![cfg_attr(not(feature = "serde"), doc = "```ignore")]
+//! use kobold::prelude::*;
+//! use kobold::event::{dispatch, CustomEvent};
+//! use kobold::reexport::web_sys::HtmlElement;
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Item {
+//!     id: u32,
+//! }
+//!
+//! #[component]
+//! fn picker(on_pick: impl Fn(u32) + 'static) -> impl View {
+//!     view! {
+//!         <div onitem-selected={move |e: CustomEvent<HtmlElement, Item>| {
+//!             if let Some(item) = e.detail() {
+//!                 on_pick(item.id);
+//!             }
+//!         }}>
+//!             <button onclick={|e| { dispatch(&e.target(), "item-selected", &Item { id: 1 }); }}>
+//!                 "Pick"
+//!             </button>
+//!         </div>
+//!     }
+//! }
+//! # use kobold::event::Event;
+//! # use kobold::reexport::web_sys::HtmlInputElement;
+//! # #[component]
+//! # fn annotated() -> impl View {
+//! #     view! {
+//! #         <p onitem-selected={|e: CustomEvent<HtmlElement, Item>| { let _: Option<Item> = e.detail(); }}>
+//! #             <input oninput={|e: Event<HtmlInputElement>| { let _ = e.current_target().value(); }}>
+//! #         </p>
+//! #     }
+//! # }
+//! # fn main() {
+//! # assert_eq!(
+//! #     kobold::render_to_string(view! { <!picker on_pick={|_| {}}> }),
+//! #     "<div><button>Pick</button></div>",
+//! # );
+//! # assert_eq!(kobold::render_to_string(view! { <!annotated> }), "<p><input></p>");
+//! # }
+//! ```
+//!
+//! Custom events dispatched with `dispatch` bubble, so they can be listened to on any
+//! ancestor of the element they were dispatched from, including from embedding JavaScript code.
+//!
+//! ## Global listeners
+//!
+//! Events that aren't tied to any element rendered by the view, such as keyboard shortcuts,
+//! can be handled with [`window_listener`] and [`document_listener`]. The listener is removed
+//! once the product of the view is dropped. Handlers bound to state need to be turned into
+//! listeners explicitly, which the `view!` macro otherwise does for element attributes:
+//!
+//! ```
+//! use kobold::prelude::*;
+//! use kobold::event::window_listener;
+//! use kobold::reexport::web_sys::Window;
+//!
+//! #[component]
+//! fn shortcuts() -> impl View {
+//!     stateful(false, |open| {
+//!         let toggle = open.bind(|open, e: KeyboardEvent<Window>| {
+//!             if e.key() == "k" && e.ctrl_key() {
+//!                 *open = !*open;
+//!             }
+//!         });
+//!
+//!         view! {
+//!             { window_listener("keydown", toggle.into_listener()) }
+//!             <p>{ open.then_some("Command palette") }</p>
+//!         }
+//!     })
+//! }
+//! # fn main() {}
+//! ```
 
 use std::marker::PhantomData;
 use std::ops::Deref;
+use std::str::FromStr;
 
 use wasm_bindgen::prelude::wasm_bindgen;
 use wasm_bindgen::{JsCast, JsValue};
-use web_sys::{HtmlElement, HtmlInputElement};
+use web_sys::{Element, HtmlElement, HtmlInputElement, Node};
 
+use crate::dom::Anchor;
+use crate::hydrate::Cursor;
 use crate::internal::{self, In, Out};
+use crate::ssr::Html;
+use crate::{init, View};
 
 #[wasm_bindgen]
 extern "C" {
@@ -92,8 +261,132 @@ event! {
     KeyboardEvent,
     /// [`web_sys::MouseEvent`](web_sys::MouseEvent)
     MouseEvent,
+    /// [`web_sys::InputEvent`](web_sys::InputEvent)
+    InputEvent,
+    /// [`web_sys::FocusEvent`](web_sys::FocusEvent)
+    FocusEvent,
+    /// [`web_sys::PointerEvent`](web_sys::PointerEvent)
+    PointerEvent,
+    /// [`web_sys::WheelEvent`](web_sys::WheelEvent)
+    WheelEvent,
+    /// [`web_sys::DragEvent`](web_sys::DragEvent)
+    DragEvent,
+    /// [`web_sys::TouchEvent`](web_sys::TouchEvent)
+    TouchEvent,
+    /// [`web_sys::SubmitEvent`](web_sys::SubmitEvent)
+    SubmitEvent,
+    /// [`web_sys::AnimationEvent`](web_sys::AnimationEvent)
+    AnimationEvent,
+    /// [`web_sys::TransitionEvent`](web_sys::TransitionEvent)
+    TransitionEvent,
+    /// [`web_sys::ClipboardEvent`](web_sys::ClipboardEvent)
+    ClipboardEvent,
 }
 
+/// Smart wrapper around a [`web_sys::CustomEvent`] which includes the type
+/// information of the event target, and the type `D` its `detail` is deserialized into.
+///
+/// Requires the `serde` feature, see the [module documentation](self#custom-events).
+#[cfg(feature = "serde")]
+#[repr(transparent)]
+pub struct CustomEvent<T, D> {
+    event: web_sys::CustomEvent,
+    _target: PhantomData<T>,
+    _detail: PhantomData<D>,
+}
+
+#[cfg(feature = "serde")]
+mod custom {
+    use serde::de::DeserializeOwned;
+    use serde::Serialize;
+
+    use super::*;
+
+    impl<T, D> From<web_sys::Event> for CustomEvent<T, D> {
+        fn from(event: web_sys::Event) -> Self {
+            CustomEvent {
+                event: event.unchecked_into(),
+                _target: PhantomData,
+                _detail: PhantomData,
+            }
+        }
+    }
+
+    impl<T, D> EventCast for CustomEvent<T, D> {}
+
+    impl<T, D> Deref for CustomEvent<T, D> {
+        type Target = web_sys::CustomEvent;
+
+        fn deref(&self) -> &Self::Target {
+            &self.event
+        }
+    }
+
+    impl<T, D> CustomEvent<T, D> {
+        /// Return a reference to the target element.
+        ///
+        /// This method shadows over the [`Event::target`](web_sys::Event::target)
+        /// method provided by `web-sys` and makes it infallible.
+        pub fn target(&self) -> HtmlElement {
+            self.event
+                .unchecked_ref::<EventWithTarget>()
+                .target()
+                .unchecked_into()
+        }
+
+        /// Return a reference to the element the listener is attached to.
+        ///
+        /// This method shadows over the [`Event::current_target`](web_sys::Event::current_target)
+        /// method provided by `web-sys` and makes it infallible.
+        pub fn current_target(&self) -> EventTarget<T>
+        where
+            T: JsCast,
+        {
+            EventTarget(
+                self.event
+                    .unchecked_ref::<EventWithTarget>()
+                    .current_target()
+                    .unchecked_into(),
+            )
+        }
+
+        /// Deserialize the `detail` of this event.
+        ///
+        /// This method shadows over the [`CustomEvent::detail`](web_sys::CustomEvent::detail)
+        /// method provided by `web-sys`, returning `None` if the `detail` can't be
+        /// deserialized into `D`.
+        pub fn detail(&self) -> Option<D>
+        where
+            D: DeserializeOwned,
+        {
+            serde_wasm_bindgen::from_value(self.event.detail()).ok()
+        }
+    }
+
+    /// Dispatch a bubbling [`CustomEvent`] named `event` from the `target` element,
+    /// with `detail` serialized into a JavaScript value.
+    ///
+    /// Returns `false` if the event was cancelled by one of its listeners.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `detail` can't be serialized, such as a map with non-string keys.
+    pub fn dispatch<D>(target: &impl AsRef<JsValue>, event: &str, detail: &D) -> bool
+    where
+        D: Serialize + ?Sized,
+    {
+        // Plain objects rather than `Map`s, so that JavaScript listeners can read the detail too
+        let detail = detail
+            .serialize(&serde_wasm_bindgen::Serializer::json_compatible())
+            .expect("Failed to serialize event detail");
+
+        internal::dispatch_custom_event(target.as_ref(), event, &detail)
+    }
+}
+
+#[cfg(feature = "serde")]
+pub use custom::dispatch;
+
 pub trait IntoListener<E: EventCast> {
     type Listener: Listener<E>;
 
@@ -164,6 +457,141 @@ where
     }
 }
 
+/// Create a [`View`] listening to `event` on the global `window` object,
+/// see the [module documentation](self#global-listeners).
+///
+/// The listener is added when the view is built, and removed when its product is dropped.
+/// Updating the view swaps the handler without touching the listener on the `window`.
+/// Nothing is rendered to HTML.
+pub fn window_listener<E, L>(event: &'static str, listener: L) -> GlobalListener<E, L::Listener>
+where
+    E: EventCast,
+    L: IntoListener<E>,
+{
+    GlobalListener {
+        target: GlobalTarget::Window,
+        event,
+        listener: listener.into_listener(),
+        _event: PhantomData,
+    }
+}
+
+/// Same as [`window_listener`], but listening to `event` on the `document`.
+pub fn document_listener<E, L>(event: &'static str, listener: L) -> GlobalListener<E, L::Listener>
+where
+    E: EventCast,
+    L: IntoListener<E>,
+{
+    GlobalListener {
+        target: GlobalTarget::Document,
+        event,
+        listener: listener.into_listener(),
+        _event: PhantomData,
+    }
+}
+
+enum GlobalTarget {
+    Window,
+    Document,
+}
+
+impl GlobalTarget {
+    fn js(self) -> JsValue {
+        let window = web_sys::window().expect("Missing `window` object");
+
+        match self {
+            GlobalTarget::Window => window.into(),
+            GlobalTarget::Document => window.document().expect("Missing `document` object").into(),
+        }
+    }
+}
+
+pub struct GlobalListener<E, L> {
+    target: GlobalTarget,
+    event: &'static str,
+    listener: L,
+    _event: PhantomData<E>,
+}
+
+pub struct GlobalListenerProduct<P> {
+    /// Empty node at the location of the view in its parent.
+    marker: Node,
+    target: JsValue,
+    event: &'static str,
+    handler: JsValue,
+    listener: P,
+}
+
+impl<E, L> GlobalListener<E, L>
+where
+    E: EventCast,
+    L: Listener<E>,
+{
+    fn build_with(
+        self,
+        marker: Node,
+        p: In<GlobalListenerProduct<L::Product>>,
+    ) -> Out<GlobalListenerProduct<L::Product>> {
+        let target = self.target.js();
+
+        p.in_place(|p| unsafe {
+            init!(p.marker = marker);
+            init!(p.event = self.event);
+
+            let mut listener = init!(p.listener @ self.listener.build(p));
+            let handler = init!(p.handler = listener.js_value());
+
+            internal::add_listener_with(&target, self.event, &handler, 0);
+
+            init!(p.target = target);
+
+            Out::from_raw(p)
+        })
+    }
+}
+
+impl<E, L> View for GlobalListener<E, L>
+where
+    E: EventCast,
+    L: Listener<E>,
+    L::Product: 'static,
+{
+    type Product = GlobalListenerProduct<L::Product>;
+
+    fn build(self, p: In<Self::Product>) -> Out<Self::Product> {
+        self.build_with(internal::empty_node(), p)
+    }
+
+    fn update(self, p: &mut Self::Product) {
+        // The handler calls into the listener product in place, so it's enough to update it.
+        self.listener.update(&mut p.listener);
+    }
+
+    fn hydrate<'p>(self, p: In<'p, Self::Product>, c: &mut Cursor) -> Out<'p, Self::Product> {
+        self.build_with(c.marker(), p)
+    }
+
+    fn render_html(self, _: &mut Html) {}
+}
+
+impl<P> Drop for GlobalListenerProduct<P> {
+    fn drop(&mut self) {
+        internal::remove_listener(&self.target, self.event, &self.handler);
+    }
+}
+
+impl<P> Anchor for GlobalListenerProduct<P>
+where
+    P: 'static,
+{
+    type Js = Node;
+    type Target = Node;
+
+    fn anchor(&self) -> &Node {
+        &self.marker
+    }
+}
+
 /// A wrapper over some event target type from web-sys.
 #[repr(transparent)]
 pub struct EventTarget<T>(T);
@@ -176,8 +604,121 @@ impl<T> Deref for EventTarget<T> {
     }
 }
 
+impl<T> EventTarget<T>
+where
+    T: AsRef<Element>,
+{
+    /// Read the `data-{name}` attribute of the target, set with `data:{name}={value}`
+    /// in the [`view!`](crate::view) macro, parsed into `V`.
+    ///
+    /// Returns `None` if the attribute is missing or can't be parsed.
+    ///
+    /// ```
+    /// use kobold::prelude::*;
+    ///
+    /// #[component]
+    /// fn item(id: u32, on_pick: impl Fn(u32) + 'static) -> impl View {
+    ///     view! {
+    ///         <li data:id={id} onclick={move |e| {
+    ///             if let Some(id) = e.current_target().data("id") {
+    ///                 on_pick(id);
+    ///             }
+    ///         }}>
+    ///     }
+    /// }
+    /// # fn main() {}
+    /// ```
+    pub fn data<V>(&self, name: &str) -> Option<V>
+    where
+        V: FromStr,
+    {
+        self.attr("data-", name)?.parse().ok()
+    }
+
+    /// Read the `aria-{name}` attribute of the target, set with `aria:{name}={value}`
+    /// in the [`view!`](crate::view) macro.
+    pub fn aria(&self, name: &str) -> Option<String> {
+        self.attr("aria-", name)
+    }
+
+    fn attr(&self, prefix: &str, name: &str) -> Option<String> {
+        self.0.as_ref().get_attribute(&format!("{prefix}{name}"))
+    }
+}
+
 impl EventTarget<HtmlInputElement> {
     pub fn focus(&self) {
         drop(self.0.focus());
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn render_nothing_to_html() {
+        let view = document_listener("keydown", |_: KeyboardEvent<()>| {});
+
+        assert_eq!(crate::render_to_string(view), "");
+    }
+
+    /// Run with `wasm-pack test --headless --chrome --features serde`
+    #[cfg(all(feature = "serde", target_arch = "wasm32"))]
+    mod custom {
+        use std::cell::RefCell;
+        use std::rc::Rc;
+
+        use serde::{Deserialize, Serialize};
+        use wasm_bindgen::closure::Closure;
+        use wasm_bindgen_test::{wasm_bindgen_test, wasm_bindgen_test_configure};
+
+        use super::*;
+
+        wasm_bindgen_test_configure!(run_in_browser);
+
+        #[derive(Serialize, Deserialize, PartialEq, Debug)]
+        struct Item {
+            id: u32,
+            name: String,
+        }
+
+        /// Dispatch `detail` from a fresh element, returning what a listener received.
+        fn round_trip<D: Serialize + ?Sized>(detail: &D) -> Option<Item> {
+            let el = web_sys::window()
+                .and_then(|window| window.document())
+                .and_then(|document| document.create_element("div").ok())
+                .expect("Failed to create an element");
+
+            let received = Rc::new(RefCell::new(None));
+            let listener = Closure::<dyn FnMut(web_sys::Event)>::new({
+                let received = received.clone();
+
+                move |e| *received.borrow_mut() = CustomEvent::<HtmlElement, Item>::from(e).detail()
+            });
+
+            el.add_event_listener_with_callback("item-selected", listener.as_ref().unchecked_ref())
+                .expect("Failed to add a listener");
+
+            assert!(dispatch(&el, "item-selected", detail));
+
+            let detail = received.borrow_mut().take();
+            detail
+        }
+
+        #[wasm_bindgen_test]
+        fn detail_round_trip() {
+            let item = Item {
+                id: 7,
+                name: "Seven".into(),
+            };
+
+            assert_eq!(round_trip(&item), Some(item));
+        }
+
+        #[wasm_bindgen_test]
+        fn mismatched_detail() {
+            assert_eq!(round_trip("not an item"), None);
+        }
+    }
+}
@@ -0,0 +1,340 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! # Hydration
+//!
+//! Markup produced by [`render_to_string`](crate::render_to_string) on the server can be
+//! brought to life in the browser with [`kobold::hydrate`](crate::hydrate()). Instead of
+//! building new DOM nodes, [`View::hydrate`] walks the existing DOM with a [`Cursor`]
+//! and adopts the nodes into its product, attaching event listeners along the way:
+//!
+//! ```no_run
+//! use kobold::prelude::*;
+//!
+//! #[component]
+//! fn hello(name: &str) -> impl View + '_ {
+//!     view! {
+//!         <h1>"Hello "{ name }"!"</h1>
+//!     }
+//! }
+//!
+//! fn main() {
+//!     // `<body>` contains `<h1>Hello Kobold!</h1>` rendered on the server
+//!     kobold::hydrate(view! {
+//!         <!hello name="Kobold">
+//!     });
+//! }
+//! ```
+//!
+//! Server-rendered markup doesn't need any extra annotations. Adjacent text nodes that were
+//! merged by the HTML parser are split again, and empty nodes marking the boundaries of
+//! lists and other fragments are inserted as needed.
+//!
+//! If the markup doesn't match the view, the mismatched subtree is built from scratch and
+//! replaces the stale node, so that it can't be adopted by any of the following views. Mismatches are reported in the console on debug builds.
+
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::{Element, Node, Text};
+
+use crate::dom::{Fragment, Mountable};
+use crate::internal::{self, In, Out};
+use crate::View;
+
+const ELEMENT_NODE: u16 = 1;
+const TEXT_NODE: u16 = 3;
+const COMMENT_NODE: u16 = 8;
+
+/// Position in the DOM used to [`hydrate`](View::hydrate) views.
+///
+/// Children of a `Cursor` are either adopted from the server-rendered DOM, or created
+/// fresh if the `Cursor` is inside a subtree that didn't match its view.
+pub struct Cursor {
+    parent: Node,
+    next: Option<Node>,
+    fresh: bool,
+}
+
+impl Cursor {
+    /// Create a new `Cursor` adopting children of the `parent` node.
+    pub fn new(parent: Node) -> Self {
+        Cursor {
+            next: parent.first_child(),
+            parent,
+            fresh: false,
+        }
+    }
+
+    fn fresh(parent: Node) -> Self {
+        Cursor {
+            parent,
+            next: None,
+            fresh: true,
+        }
+    }
+
+    /// The node whose children this `Cursor` is walking.
+    pub fn node(&self) -> &Node {
+        &self.parent
+    }
+
+    /// Adopt the next element with the given `tag`, returning a `Cursor` for its children.
+    pub fn element(&mut self, tag: &str) -> Cursor {
+        self.element_with(tag, || internal::create_element(tag))
+    }
+
+    /// Same as [`element`](Cursor::element) for elements in the namespace `ns`, such as SVG.
+    pub fn element_ns(&mut self, ns: &str, tag: &str) -> Cursor {
+        self.element_with(tag, || internal::create_element_ns(ns, tag))
+    }
+
+    fn element_with(&mut self, tag: &str, create: impl FnOnce() -> Node) -> Cursor {
+        if !self.fresh {
+            self.skip(true);
+
+            if let Some(node) = &self.next {
+                if is_element(node, tag) {
+                    let node = node.clone();
+
+                    self.next = node.next_sibling();
+
+                    return Cursor::new(node);
+                }
+            }
+
+            mismatch(format_args!("<{tag}>"), self.next.as_ref());
+        }
+
+        let el = create();
+
+        self.replace_next(&el);
+
+        Cursor::fresh(el)
+    }
+
+    /// Adopt the next text node containing `text`. If the server-rendered text node
+    /// contains more text it will be split.
+    pub fn text(&mut self, text: &str) -> Node {
+        if !self.fresh && !text.is_empty() {
+            self.skip(false);
+
+            if let Some(node) = &self.next {
+                let data = node.node_value().unwrap_or_default();
+
+                if node.node_type() == TEXT_NODE && data.starts_with(text) {
+                    let node = node.clone();
+
+                    self.next = if data.len() > text.len() {
+                        let split = text.encode_utf16().count() as u32;
+
+                        node.unchecked_ref::<Text>()
+                            .split_text(split)
+                            .ok()
+                            .map(Into::into)
+                    } else {
+                        node.next_sibling()
+                    };
+
+                    return node;
+                }
+            }
+
+            mismatch(format_args!("text {text:?}"), self.next.as_ref());
+        }
+
+        let node = internal::text_node(text);
+
+        // Empty text is never rendered on the server, so there is nothing to replace
+        if text.is_empty() {
+            self.insert(&node);
+        } else {
+            self.replace_next(&node);
+        }
+
+        node
+    }
+
+    /// Insert an empty text node at the cursor. Those don't survive being rendered to HTML,
+    /// and are used by **Kobold** to mark boundaries of fragments or empty views.
+    pub fn marker(&mut self) -> Node {
+        let node = internal::empty_node();
+
+        self.insert(&node);
+
+        node
+    }
+
+    /// Start a fragment at the cursor. Nodes adopted through the returned [`FragmentCursor`]
+    /// belong to the fragment.
+    pub fn fragment(&mut self) -> FragmentCursor<'_> {
+        FragmentCursor {
+            head: self.marker(),
+            cursor: self,
+        }
+    }
+
+    /// Insert a new node at the cursor.
+    pub fn insert(&mut self, node: &JsValue) {
+        match &self.next {
+            Some(next) => internal::obj(next).append_before(node),
+            None => internal::obj(&self.parent).append(node),
+        }
+    }
+
+    /// Insert a new node in place of the next node, which didn't match the view and
+    /// so must not be adopted by any of the following views.
+    fn replace_next(&mut self, node: &JsValue) {
+        match self.next.take() {
+            Some(stale) => {
+                self.next = stale.next_sibling();
+
+                internal::obj(&stale).replace(node);
+            }
+            None => internal::obj(&self.parent).append(node),
+        }
+    }
+
+    /// Build a view from scratch and insert it at the cursor. Views that can't
+    /// adopt existing DOM nodes use this, which is the default [`View::hydrate`].
+    pub fn rebuild<'p, V: View>(&mut self, view: V, p: In<'p, V::Product>) -> Out<'p, V::Product> {
+        let prod = view.build(p);
+
+        self.insert(prod.js());
+
+        prod
+    }
+
+    /// Add a class to a newly created element, server-rendered markup already contains it.
+    pub fn class(&self, class: &str) {
+        if self.fresh {
+            internal::add_class(&self.parent, class);
+        }
+    }
+
+    /// Set an attribute on a newly created element, server-rendered markup already contains it.
+    pub fn attr(&self, name: &str, value: &str) {
+        if self.fresh {
+            internal::obj(&self.parent).set_attr(name, value);
+        }
+    }
+
+    /// Set a CSS property on a newly created element, server-rendered markup already contains it.
+    pub fn style(&self, property: &str, value: &str) {
+        if self.fresh {
+            internal::set_style(&self.parent, property, value);
+        }
+    }
+
+    /// Set a boolean property on a newly created element, server-rendered markup already contains it.
+    pub fn prop(&self, name: &str, value: bool) {
+        if self.fresh {
+            internal::set_prop(&self.parent, name, value);
+        }
+    }
+
+    /// Add an event listener to the element.
+    pub fn listen(&self, event: &str, listener: &JsValue) {
+        internal::obj(&self.parent).add_listener(event, listener);
+    }
+
+    /// Add an event listener to the element with options set by modifiers in the
+    /// [`view!`](crate::view) macro, as bit flags: `capture`, `passive`, `once`,
+    /// `prevent` and `stop`, starting from the least significant bit.
+    pub fn listen_with(&self, event: &str, listener: &JsValue, flags: u8) {
+        internal::add_listener_with(&self.parent, event, listener, flags);
+    }
+
+    /// Remove all remaining nodes that weren't adopted by any view, other than
+    /// whitespace, comments and scripts.
+    pub fn finish(&mut self) {
+        let mut next = self.next.take();
+
+        while let Some(node) = next {
+            next = node.next_sibling();
+
+            if is_ignored(&node, true) || is_element(&node, "script") {
+                continue;
+            }
+
+            mismatch(format_args!("end of children"), Some(&node));
+
+            internal::obj(&node).unmount();
+        }
+    }
+
+    fn skip(&mut self, whitespace: bool) {
+        while let Some(node) = &self.next {
+            if !is_ignored(node, whitespace) {
+                break;
+            }
+            self.next = node.next_sibling();
+        }
+    }
+}
+
+impl From<Cursor> for Node {
+    fn from(cursor: Cursor) -> Node {
+        cursor.parent
+    }
+}
+
+/// Cursor adopting nodes into a [`Fragment`], see [`Cursor::fragment`].
+pub struct FragmentCursor<'a> {
+    cursor: &'a mut Cursor,
+    head: Node,
+}
+
+impl std::ops::Deref for FragmentCursor<'_> {
+    type Target = Cursor;
+
+    fn deref(&self) -> &Cursor {
+        self.cursor
+    }
+}
+
+impl std::ops::DerefMut for FragmentCursor<'_> {
+    fn deref_mut(&mut self) -> &mut Cursor {
+        self.cursor
+    }
+}
+
+impl From<FragmentCursor<'_>> for Fragment {
+    fn from(fragment: FragmentCursor) -> Fragment {
+        let tail = fragment.cursor.marker();
+
+        Fragment::adopt(&fragment.head, &tail)
+    }
+}
+
+fn is_element(node: &Node, tag: &str) -> bool {
+    node.node_type() == ELEMENT_NODE && node.unchecked_ref::<Element>().local_name() == tag
+}
+
+fn is_ignored(node: &Node, whitespace: bool) -> bool {
+    match node.node_type() {
+        COMMENT_NODE => true,
+        TEXT_NODE if whitespace => match node.node_value() {
+            Some(data) => data.trim().is_empty(),
+            None => true,
+        },
+        _ => false,
+    }
+}
+
+#[cfg(debug_assertions)]
+fn mismatch(expected: std::fmt::Arguments, found: Option<&Node>) {
+    let found = match found {
+        Some(node) if node.node_type() == TEXT_NODE => {
+            format!("text {:?}", node.node_value().unwrap_or_default())
+        }
+        Some(node) => node.node_name().to_lowercase(),
+        None => "nothing".into(),
+    };
+
+    internal::warn(&format!(
+        "Kobold hydration mismatch: expected {expected}, found {found}"
+    ));
+}
+
+#[cfg(not(debug_assertions))]
+fn mismatch(_: std::fmt::Arguments, _: Option<&Node>) {}
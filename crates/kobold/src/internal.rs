@@ -11,6 +11,8 @@ use std::pin::Pin;
 use wasm_bindgen::prelude::*;
 use web_sys::Node;
 
+use crate::hydrate::Cursor;
+use crate::ssr::Html;
 use crate::View;
 
 /// Uninitialized stable pointer to `T`.
@@ -192,6 +194,18 @@ macro_rules! init {
 #[repr(transparent)]
 pub struct Precompiled<F>(pub F);
 
+impl<F> Precompiled<F> {
+    /// Pair the precompiled function with functions rendering the same markup
+    /// to [`Html`] and hydrating it.
+    pub const fn with_html<H, Y>(self, html: H, hydrate: Y) -> PrecompiledHtml<F, H, Y> {
+        PrecompiledHtml(self, html, hydrate)
+    }
+}
+
+/// [`Precompiled`] view that can also be rendered to [`Html`] and hydrated,
+/// see [`Precompiled::with_html`].
+pub struct PrecompiledHtml<F, H, Y>(Precompiled<F>, H, Y);
+
 /// Helper function used by the [`view!`](crate::view) macro to provide type hints for
 /// event listeners.
 #[inline]
@@ -210,6 +224,36 @@ where
     }
 
     fn update(self, _: &mut Node) {}
+
+    /// # Panics
+    ///
+    /// Always, there is no markup to render without the DOM, see [`Precompiled::with_html`].
+    fn render_html(self, _: &mut Html) {
+        panic!("Precompiled view can't be rendered to HTML, use `Precompiled::with_html`");
+    }
+}
+
+impl<F, H, Y> View for PrecompiledHtml<F, H, Y>
+where
+    F: Fn() -> Node,
+    H: Fn(&mut Html),
+    Y: Fn(&mut Cursor) -> Node,
+{
+    type Product = Node;
+
+    fn build(self, p: In<Node>) -> Out<Node> {
+        self.0.build(p)
+    }
+
+    fn update(self, _: &mut Node) {}
+
+    fn hydrate<'p>(self, p: In<'p, Node>, c: &mut Cursor) -> Out<'p, Node> {
+        p.put((self.2)(c))
+    }
+
+    fn render_html(self, html: &mut Html) {
+        (self.1)(html)
+    }
 }
 
 #[wasm_bindgen]
@@ -224,11 +268,25 @@ extern "C" {
     pub(crate) fn text_node_num(t: f64) -> Node;
     #[wasm_bindgen(js_namespace = document, js_name = createTextNode)]
     pub(crate) fn text_node_bool(t: bool) -> Node;
+    #[wasm_bindgen(js_namespace = document, js_name = createElement)]
+    pub(crate) fn create_element(tag: &str) -> Node;
+    #[wasm_bindgen(js_namespace = document, js_name = createElementNS)]
+    pub(crate) fn create_element_ns(ns: &str, tag: &str) -> Node;
+
+    #[wasm_bindgen(js_namespace = document, js_name = querySelector)]
+    pub(crate) fn query_selector(selector: &str) -> Option<Node>;
+
+    #[wasm_bindgen(js_namespace = console, js_name = warn)]
+    pub(crate) fn warn(msg: &str);
 
     // dom manipulation ----------------
 
     #[wasm_bindgen(method, js_name = "before")]
     pub(crate) fn append_before(this: &UnsafeNode, insert: &JsValue);
+    #[wasm_bindgen(method, js_name = "append")]
+    pub(crate) fn append(this: &UnsafeNode, insert: &JsValue);
+    #[wasm_bindgen(method, js_name = "addEventListener")]
+    pub(crate) fn add_listener(this: &UnsafeNode, event: &str, listener: &JsValue);
     #[wasm_bindgen(method, js_name = "remove")]
     pub(crate) fn unmount(this: &UnsafeNode);
     #[wasm_bindgen(method, js_name = "replaceWith")]
@@ -251,6 +309,10 @@ extern "C" {
     pub(crate) fn set_attr_num(this: &UnsafeNode, a: &str, v: f64);
     #[wasm_bindgen(method, js_name = "setAttribute")]
     pub(crate) fn set_attr_bool(this: &UnsafeNode, a: &str, v: bool);
+    #[wasm_bindgen(method, js_name = "removeAttribute")]
+    pub(crate) fn remove_attr(this: &UnsafeNode, a: &str);
+    #[wasm_bindgen(method, js_name = "toggleAttribute")]
+    pub(crate) fn toggle_attr(this: &UnsafeNode, a: &str, v: bool);
 
     // provided attribute setters ----------------
 
@@ -291,6 +353,12 @@ extern "C" {
     pub(crate) fn fragment() -> Node;
     #[wasm_bindgen(js_name = "fragmentDecorate")]
     pub(crate) fn fragment_decorate(f: &Node) -> Node;
+    #[wasm_bindgen(js_name = "fragmentAdopt")]
+    pub(crate) fn fragment_adopt(head: &Node, tail: &Node) -> Node;
+    #[wasm_bindgen(js_name = "insertBefore")]
+    pub(crate) fn insert_before(anchor: &JsValue, insert: &JsValue);
+    #[wasm_bindgen(js_name = "moveBefore")]
+    pub(crate) fn move_before(anchor: &JsValue, moved: &JsValue);
     #[wasm_bindgen(js_name = "fragmentUnmount")]
     pub(crate) fn fragment_unmount(f: &Node);
     #[wasm_bindgen(js_name = "fragmentReplace")]
@@ -300,6 +368,17 @@ extern "C" {
 
     #[wasm_bindgen(js_name = "setChecked")]
     pub(crate) fn checked(node: &Node, value: bool);
+    #[wasm_bindgen(js_name = "setProp")]
+    pub(crate) fn set_prop(node: &Node, prop: &str, value: bool);
+    #[wasm_bindgen(js_name = "setStyle")]
+    pub(crate) fn set_style(node: &Node, prop: &str, value: &str);
+    #[wasm_bindgen(js_name = "setStyle")]
+    pub(crate) fn set_style_num(node: &Node, prop: &str, value: f64);
+
+    #[wasm_bindgen(js_name = "documentBody")]
+    pub(crate) fn document_body() -> Node;
+    #[wasm_bindgen(js_name = "injectStyle")]
+    pub(crate) fn inject_style(css: &str);
 
     // ----------------
 
@@ -314,6 +393,15 @@ extern "C" {
 
     // ----------------
 
+    #[wasm_bindgen(js_name = "addListener")]
+    pub(crate) fn add_listener_with(target: &JsValue, event: &str, listener: &JsValue, flags: u8);
+    #[wasm_bindgen(js_name = "removeListener")]
+    pub(crate) fn remove_listener(target: &JsValue, event: &str, listener: &JsValue);
+
+    #[cfg(feature = "serde")]
+    #[wasm_bindgen(js_name = "dispatchCustomEvent")]
+    pub(crate) fn dispatch_custom_event(target: &JsValue, event: &str, detail: &JsValue) -> bool;
+
     #[wasm_bindgen(js_name = "makeEventHandler")]
     pub(crate) fn make_event_handler(closure: *mut (), vcall: usize) -> JsValue;
 
@@ -346,6 +434,7 @@ mod test {
     //
     // This is a small mock of what the macro does however.
     #[test]
+    #[allow(clippy::approx_constant)]
     fn build_in_place() {
         fn meaning_builder(p: In<u32>) -> Out<u32> {
             p.put(42)
@@ -5,10 +5,12 @@
 //! Keyword handles for `{ ... }` expressions in the [`view!`](crate::view) macro.
 
 use crate::diff::{Eager, Ref, Static};
-use crate::list::{Bounded, List};
-use crate::View;
+use crate::list::{Bounded, List, ListItem};
 
-/// `{ for ... }`: turn an [`IntoIterator`] type into a [`View`].
+/// `{ for ... }`: turn an [`IntoIterator`] type into a [`View`](crate::View).
+///
+/// Items are diffed by their position in the list, unless they are wrapped with
+/// [`keyed`](crate::list::keyed()), in which case they are diffed by their keys.
 ///
 /// ```
 /// # use kobold::prelude::*;
@@ -23,12 +25,12 @@ use crate::View;
 pub const fn r#for<T>(iterator: T) -> List<T>
 where
     T: IntoIterator,
-    T::Item: View,
+    T::Item: ListItem,
 {
     List::new(iterator)
 }
 
-/// `{ for<N> ... }`: turn an [`IntoIterator`] type into a [`View`],
+/// `{ for<N> ... }`: turn an [`IntoIterator`] type into a [`View`](crate::View),
 /// bounded to max length of `N`.
 ///
 /// This should be used only for small values of `N`.
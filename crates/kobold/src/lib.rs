@@ -54,6 +54,7 @@
 //!
 //! The component function must return a type that implements the [`View`] trait. Since the [`view!`](view) macro
 //! produces transient locally defined types the best approach here is to always use the opaque `impl View` return type.
+//! Components that can fail may return `Result<impl View, E>` instead, see [fallible components](branching#fallible-components).
 //!
 //! Everything here is statically typed and the macro doesn't delete any information when manipulating the
 //! token stream, so the Rust compiler can tell you when you've made a mistake:
@@ -164,6 +165,27 @@
 //! # fn main() {}
 //! ```
 //!
+//! Conditionals can also be written inline, next to the markup they affect:
+//!
+//! ```
+//! # use kobold::prelude::*;
+//! #[component]
+//! fn conditional(illuminatus: bool) -> impl View {
+//!     view! {
+//!         <div>
+//!         {
+//!             if illuminatus {
+//!                 <p> "It was the year when they finally immanentized the Eschaton."
+//!             } else {
+//!                 <blockquote> "It was love at first sight."
+//!             }
+//!         }
+//!         </div>
+//!     }
+//! }
+//! # fn main() {}
+//! ```
+//!
 //! For more details visit the [`branching` module documentation](branching).
 //!
 //! ### Lists and Iterators
@@ -255,6 +277,25 @@
 //! }
 //! ```
 //!
+//! ### Server-side rendering
+//!
+//! Any [`View`] can also be rendered to an HTML string with [`render_to_string`]. This doesn't need
+//! a browser, so the same components can be used to pre-render pages on a native host:
+//!
+//! ```
+//! use kobold::prelude::*;
+//!
+//! let html = kobold::render_to_string(view! {
+//!     <p>"Meaning of life is "{ 42 }</p>
+//! });
+//!
+//! assert_eq!(html, "<p>Meaning of life is 42</p>");
+//! ```
+//!
+//! Pre-rendered pages can then be made interactive in the browser with [`kobold::hydrate`](hydrate()),
+//! which adopts the existing DOM instead of building it again. For more details visit the
+//! [`ssr`] and [`hydrate`](mod@hydrate) module documentation.
+//!
 //! ## More Examples
 //!
 //! To run **Kobold** you'll need to install [`trunk`](https://trunkrs.dev/):
@@ -399,7 +440,13 @@ pub use kobold_macros::component;
 /// Macro for creating transient [`View`] types. See the [main documentation](crate) for details.
 pub use kobold_macros::{class, view};
 
-use wasm_bindgen::JsCast;
+/// Macro for declaring a stylesheet with class names scoped to it. See the
+/// [`style` module documentation](style) for details.
+pub use kobold_macros::css;
+
+use std::cell::RefCell;
+
+use wasm_bindgen::{JsCast, JsValue};
 
 #[cfg(all(
     target_arch = "wasm32",
@@ -410,21 +457,36 @@ use wasm_bindgen::JsCast;
 static A: rlsf::SmallGlobalTlsf = rlsf::SmallGlobalTlsf::new();
 
 pub mod attribute;
+pub mod boxed;
 pub mod branching;
+pub mod context;
 pub mod diff;
 pub mod dom;
+pub mod effect;
+pub mod error;
 pub mod event;
+pub mod hydrate;
 pub mod internal;
 pub mod keywords;
 pub mod list;
 pub mod maybe;
+pub mod portal;
+pub mod ssr;
+pub mod style;
 
 mod value;
 
 #[cfg(feature = "stateful")]
 pub mod stateful;
 
+use boxed::BoxedView;
+use diff::Diff;
+use effect::{Cleanup, Effect};
+use hydrate::Cursor;
 use internal::{In, Out};
+use ssr::Html;
+
+pub use ssr::render_to_string;
 
 /// The prelude module with most commonly used types.
 ///
@@ -433,15 +495,16 @@ use internal::{In, Out};
 /// use kobold::prelude::*;
 /// ```
 pub mod prelude {
+    pub use crate::dom::NodeRef;
     pub use crate::event::{Event, KeyboardEvent, MouseEvent};
-    pub use crate::{bind, class, event};
+    pub use crate::{bind, class, css, event};
     pub use crate::{component, view, View};
 
     #[cfg(feature = "stateful")]
-    pub use crate::stateful::{stateful, Hook, IntoState, Signal, Then};
+    pub use crate::stateful::{stateful, Hook, IntoState, Signal, Store, Then};
 }
 
-use dom::Mountable;
+use dom::{MountTarget, Mountable, Unmount};
 
 /// Crate re-exports for the [`view!`](view) macro internals
 pub mod reexport {
@@ -461,6 +524,20 @@ pub trait View {
     /// Update the product and apply changes to the DOM if necessary.
     fn update(self, p: &mut Self::Product);
 
+    /// Build a product adopting the DOM nodes at the cursor, see [`hydrate`](mod@hydrate).
+    ///
+    /// The default implementation builds the view from scratch and inserts it at the cursor,
+    /// see [`Cursor::rebuild`].
+    fn hydrate<'p>(self, p: In<'p, Self::Product>, c: &mut Cursor) -> Out<'p, Self::Product>
+    where
+        Self: Sized,
+    {
+        c.rebuild(self, p)
+    }
+
+    /// Render this view as HTML without touching the DOM, see [`render_to_string`].
+    fn render_html(self, html: &mut Html);
+
     /// Once this view is built, do something once.
     fn on_mount<F>(self, handler: F) -> OnMount<Self, F>
     where
@@ -473,6 +550,78 @@ pub trait View {
         }
     }
 
+    /// Do something every time the product of this view is detached from the DOM,
+    /// or dropped, as described by the [`Unmount`] argument.
+    ///
+    /// This is the case when a [`BranchN`](branching) switches to another variant,
+    /// or a list shrinks. Products nested inside the detached or dropped one are not
+    /// notified of the detachment, but still notified when they are dropped.
+    /// If the handler itself causes the product to be detached again, it isn't called
+    /// for that nested detachment.
+    ///
+    /// ```
+    /// use kobold::prelude::*;
+    /// use kobold::dom::Unmount;
+    ///
+    /// #[component]
+    /// fn banner(visible: bool) -> impl View {
+    ///     visible.then(|| {
+    ///         view! { <p>"Hello!"</p> }.on_unmount(|reason| match reason {
+    ///             Unmount::Detached => println!("Banner hidden"),
+    ///             Unmount::Dropped => println!("Banner dropped"),
+    ///         })
+    ///     })
+    /// }
+    /// # fn main() {}
+    /// ```
+    fn on_unmount<F>(self, handler: F) -> OnUnmount<Self, F>
+    where
+        F: FnMut(Unmount) + 'static,
+        Self: Sized,
+    {
+        OnUnmount {
+            view: self,
+            handler,
+        }
+    }
+
+    /// Run a side `effect` after this view is built, and again after every update
+    /// that changes the `deps`. The value returned by the `effect` is [cleaned up](effect::Cleanup)
+    /// before the next run, and when the product of this view is dropped.
+    ///
+    /// ```
+    /// use kobold::prelude::*;
+    ///
+    /// fn subscribe(channel: u32) -> impl FnOnce() {
+    ///     // open a websocket...
+    ///     move || {
+    ///         // ...and close it
+    ///     }
+    /// }
+    ///
+    /// #[component]
+    /// fn chat(channel: u32) -> impl View {
+    ///     view! {
+    ///         <h1>"Channel #"{ channel }</h1>
+    ///     }
+    ///     .effect(channel, subscribe)
+    /// }
+    /// # fn main() {}
+    /// ```
+    fn effect<D, F, C>(self, deps: D, effect: F) -> Effect<Self, D, F>
+    where
+        D: Diff,
+        F: FnOnce(D) -> C,
+        C: Cleanup,
+        Self: Sized,
+    {
+        Effect {
+            view: self,
+            deps,
+            effect,
+        }
+    }
+
     /// Similar to [`on_mount`](View::on_mount) but triggers on every
     /// update, not just initial render.
     fn on_render<F>(self, handler: F) -> OnRender<Self, F>
@@ -485,6 +634,14 @@ pub trait View {
             handler,
         }
     }
+
+    /// Erase the type of this view, see the [`boxed`](mod@boxed) module.
+    fn boxed<'a>(self) -> BoxedView<'a>
+    where
+        Self: Sized + 'a,
+    {
+        BoxedView::new(self)
+    }
 }
 
 pub struct OnMount<V, F> {
@@ -510,6 +667,18 @@ where
     fn update(self, p: &mut Self::Product) {
         self.view.update(p);
     }
+
+    fn hydrate<'p>(self, p: In<'p, Self::Product>, c: &mut Cursor) -> Out<'p, Self::Product> {
+        let prod = self.view.hydrate(p, c);
+
+        (self.handler)(prod.js().unchecked_ref());
+
+        prod
+    }
+
+    fn render_html(self, html: &mut Html) {
+        self.view.render_html(html);
+    }
 }
 
 pub struct OnRender<V, F> {
@@ -537,9 +706,24 @@ where
 
         (self.handler)(p.js().unchecked_ref());
     }
+
+    fn hydrate<'p>(self, p: In<'p, Self::Product>, c: &mut Cursor) -> Out<'p, Self::Product> {
+        let prod = self.view.hydrate(p, c);
+
+        (self.handler)(prod.js().unchecked_ref());
+
+        prod
+    }
+
+    fn render_html(self, html: &mut Html) {
+        self.view.render_html(html);
+    }
 }
 
 /// Start the Kobold app by mounting given [`View`] in the document `body`.
+///
+/// The app stays mounted for the lifetime of the page, use [`mount`] to mount
+/// into a different element or to unmount the app later.
 pub fn start(view: impl View) {
     init_panic_hook();
 
@@ -555,6 +739,169 @@ pub fn start(view: impl View) {
     internal::append_body(product.js());
 }
 
+pub struct OnUnmount<V, F> {
+    view: V,
+    handler: F,
+}
+
+pub struct OnUnmountProduct<P, F: FnMut(Unmount)> {
+    product: P,
+    handler: RefCell<F>,
+}
+
+impl<V, F> View for OnUnmount<V, F>
+where
+    V: View,
+    F: FnMut(Unmount) + 'static,
+{
+    type Product = OnUnmountProduct<V::Product, F>;
+
+    fn build(self, p: In<Self::Product>) -> Out<Self::Product> {
+        p.in_place(|p| unsafe {
+            init!(p.product @ self.view.build(p));
+            init!(p.handler = RefCell::new(self.handler));
+
+            Out::from_raw(p)
+        })
+    }
+
+    fn update(self, p: &mut Self::Product) {
+        self.view.update(&mut p.product);
+
+        *p.handler.get_mut() = self.handler;
+    }
+
+    fn hydrate<'p>(self, p: In<'p, Self::Product>, c: &mut Cursor) -> Out<'p, Self::Product> {
+        p.in_place(|p| unsafe {
+            init!(p.product @ self.view.hydrate(p, c));
+            init!(p.handler = RefCell::new(self.handler));
+
+            Out::from_raw(p)
+        })
+    }
+
+    fn render_html(self, html: &mut Html) {
+        self.view.render_html(html);
+    }
+}
+
+impl<P, F> Mountable for OnUnmountProduct<P, F>
+where
+    P: Mountable,
+    F: FnMut(Unmount) + 'static,
+{
+    type Js = P::Js;
+
+    fn js(&self) -> &JsValue {
+        self.product.js()
+    }
+
+    fn unmount(&self) {
+        self.product.unmount();
+        self.detached();
+    }
+
+    fn replace_with(&self, new: &JsValue) {
+        self.product.replace_with(new);
+        self.detached();
+    }
+}
+
+impl<P, F> OnUnmountProduct<P, F>
+where
+    F: FnMut(Unmount),
+{
+    fn detached(&self) {
+        // Handler might detach this product again, such nested calls are ignored
+        if let Ok(mut handler) = self.handler.try_borrow_mut() {
+            handler(Unmount::Detached);
+        }
+    }
+}
+
+impl<P, F> Drop for OnUnmountProduct<P, F>
+where
+    F: FnMut(Unmount),
+{
+    fn drop(&mut self) {
+        (self.handler.get_mut())(Unmount::Dropped);
+    }
+}
+
+/// Mount given [`View`] into the `target` element, after all of its existing children.
+/// The `target` can be either a CSS selector, or a DOM node:
+///
+/// ```no_run
+/// use kobold::prelude::*;
+///
+/// let sidebar = kobold::mount("#sidebar", view! { <p>"Sidebar widget"</p> });
+/// let footer = kobold::mount("#footer", view! { <p>"Footer widget"</p> });
+///
+/// // Unmount the sidebar widget, dropping all of its state
+/// drop(sidebar);
+/// # drop(footer);
+/// ```
+///
+/// Unlike [`start`], this can be called any number of times to mount multiple
+/// independent islands of Kobold in a larger page. Dropping the returned [`AppHandle`]
+/// unmounts the view and frees its product, together with all of its event listeners and state.
+pub fn mount(target: impl MountTarget, view: impl View) -> AppHandle {
+    init_panic_hook();
+
+    #[cfg(debug_assertions)]
+    internal::check_event_handler();
+
+    let target = target.target();
+    let product = In::boxed(move |p| view.build(p));
+
+    internal::obj(&target).append(product.js());
+
+    AppHandle { product }
+}
+
+/// Handle to an app [mounted](mount) in the DOM, unmounts the app when dropped.
+///
+/// Use [`std::mem::forget`] to keep the app mounted for the lifetime of the page.
+#[must_use = "dropping the `AppHandle` immediately unmounts the app"]
+pub struct AppHandle {
+    product: Box<dyn AnyProduct>,
+}
+
+trait AnyProduct {
+    fn unmount(&self);
+}
+
+impl<P: Mountable> AnyProduct for P {
+    fn unmount(&self) {
+        Mountable::unmount(self);
+    }
+}
+
+impl Drop for AppHandle {
+    fn drop(&mut self) {
+        self.product.unmount();
+    }
+}
+
+/// Start the Kobold app by hydrating the server-rendered content of the document `body`
+/// with given [`View`], see the [`hydrate`](mod@hydrate) module.
+pub fn hydrate(view: impl View) {
+    init_panic_hook();
+
+    #[cfg(debug_assertions)]
+    internal::check_event_handler();
+
+    use std::mem::MaybeUninit;
+    use std::pin::pin;
+
+    let mut cursor = Cursor::new(internal::document_body());
+
+    let product = pin!(MaybeUninit::uninit());
+    let _ = In::pinned(product, |p| view.hydrate(p, &mut cursor));
+
+    cursor.finish();
+}
+
 fn init_panic_hook() {
     // Only enable console hook on debug builds
     #[cfg(debug_assertions)]
@@ -633,3 +980,58 @@ macro_rules! event {
         $state.bind(move |$state, _| $state $($body)*)
     };
 }
+
+#[cfg(test)]
+mod test {
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    use super::*;
+
+    struct Detachable(JsValue);
+
+    impl Mountable for Detachable {
+        type Js = web_sys::Node;
+
+        fn js(&self) -> &JsValue {
+            &self.0
+        }
+
+        fn unmount(&self) {}
+
+        fn replace_with(&self, _: &JsValue) {}
+    }
+
+    #[test]
+    fn unmount_from_handler() {
+        let product: Rc<Cell<Option<*const dyn Mountable<Js = web_sys::Node>>>> = Rc::default();
+        let calls = Rc::new(Cell::new(Vec::new()));
+
+        let handler = {
+            let product = product.clone();
+            let calls = calls.clone();
+
+            move |reason| {
+                let mut log = calls.take();
+                log.push(reason);
+                calls.set(log);
+
+                if let Some(product) = product.get() {
+                    unsafe { (*product).unmount() }
+                }
+            }
+        };
+
+        let p = OnUnmountProduct {
+            product: Detachable(JsValue::UNDEFINED),
+            handler: RefCell::new(handler),
+        };
+
+        product.set(Some(&p as *const _));
+        Mountable::unmount(&p);
+        product.set(None);
+        drop(p);
+
+        assert_eq!(calls.take(), [Unmount::Detached, Unmount::Dropped]);
+    }
+}
@@ -4,15 +4,20 @@
 
 //! Utilities for rendering lists
 
+use std::hash::Hash;
 use std::marker::PhantomData;
 
+use crate::hydrate::Cursor;
 use crate::internal::{In, Out};
-use crate::View;
+use crate::ssr::Html;
+use crate::{Mountable, View};
 
 pub mod bounded;
+pub mod keyed;
 pub mod unbounded;
 
 use bounded::BoundedProduct;
+use keyed::KeyedProduct;
 use unbounded::ListProduct;
 
 /// Zero-sized marker making the [`List`] unbounded: it can grow to arbitrary
@@ -38,19 +43,157 @@ impl<T> List<T> {
     }
 }
 
+/// A [`View`] inside a [`List`] that is matched to its previous render by `key`
+/// rather than by its position, created by the [`keyed`](keyed()) function.
+pub struct Keyed<K, V> {
+    key: K,
+    view: V,
+}
+
+/// Pair a [`View`] with a unique `key` inside a [`for`](crate::keywords::for) list.
+///
+/// On update, keyed lists match new views to old products by their keys: items that
+/// were inserted, removed, or reordered are moved around in the DOM with as few
+/// operations as possible, instead of every item being updated in place by its position.
+/// This preserves DOM state such as focus or text selection in the items.
+///
+/// ```
+/// use kobold::prelude::*;
+/// use kobold::list::keyed;
+///
+/// struct Todo {
+///     id: u32,
+///     text: String,
+/// }
+///
+/// #[component]
+/// fn todo_list(todos: &[Todo]) -> impl View + '_ {
+///     view! {
+///         <ul>
+///         {
+///             for todos.iter().map(|todo| keyed(todo.id, view! {
+///                 <li>{ ref todo.text }</li>
+///             }))
+///         }
+///         </ul>
+///     }
+/// }
+/// # fn main() {}
+/// ```
+pub const fn keyed<K, V>(key: K, view: V) -> Keyed<K, V>
+where
+    K: Hash + Eq + 'static,
+    V: View,
+{
+    Keyed { key, view }
+}
+
+/// Item of an unbounded [`List`]: either any [`View`], diffed by its position,
+/// or a [`Keyed`] view, diffed by its key.
+pub trait ListItem: Sized {
+    /// Product of the whole list of these items.
+    type List: Mountable;
+
+    fn build_list<I>(iter: I, p: In<Self::List>) -> Out<Self::List>
+    where
+        I: Iterator<Item = Self>;
+
+    fn update_list<I>(iter: I, p: &mut Self::List)
+    where
+        I: Iterator<Item = Self>;
+
+    fn hydrate_list<'p, I>(iter: I, p: In<'p, Self::List>, c: &mut Cursor) -> Out<'p, Self::List>
+    where
+        I: Iterator<Item = Self>;
+
+    fn render_item(self, html: &mut Html);
+}
+
+impl<V: View> ListItem for V {
+    type List = ListProduct<V::Product>;
+
+    fn build_list<I>(iter: I, p: In<Self::List>) -> Out<Self::List>
+    where
+        I: Iterator<Item = Self>,
+    {
+        ListProduct::build(iter, p)
+    }
+
+    fn update_list<I>(iter: I, p: &mut Self::List)
+    where
+        I: Iterator<Item = Self>,
+    {
+        p.update(iter);
+    }
+
+    fn hydrate_list<'p, I>(iter: I, p: In<'p, Self::List>, c: &mut Cursor) -> Out<'p, Self::List>
+    where
+        I: Iterator<Item = Self>,
+    {
+        ListProduct::hydrate(iter, p, c)
+    }
+
+    fn render_item(self, html: &mut Html) {
+        self.render_html(html);
+    }
+}
+
+impl<K, V> ListItem for Keyed<K, V>
+where
+    K: Hash + Eq + 'static,
+    V: View,
+{
+    type List = KeyedProduct<K, V::Product>;
+
+    fn build_list<I>(iter: I, p: In<Self::List>) -> Out<Self::List>
+    where
+        I: Iterator<Item = Self>,
+    {
+        KeyedProduct::build(iter, p)
+    }
+
+    fn update_list<I>(iter: I, p: &mut Self::List)
+    where
+        I: Iterator<Item = Self>,
+    {
+        p.update(iter);
+    }
+
+    fn hydrate_list<'p, I>(iter: I, p: In<'p, Self::List>, c: &mut Cursor) -> Out<'p, Self::List>
+    where
+        I: Iterator<Item = Self>,
+    {
+        KeyedProduct::hydrate(iter, p, c)
+    }
+
+    fn render_item(self, html: &mut Html) {
+        self.view.render_html(html);
+    }
+}
+
 impl<T> View for List<T>
 where
     T: IntoIterator,
-    <T as IntoIterator>::Item: View,
+    <T as IntoIterator>::Item: ListItem,
 {
-    type Product = ListProduct<<T::Item as View>::Product>;
+    type Product = <T::Item as ListItem>::List;
 
     fn build(self, p: In<Self::Product>) -> Out<Self::Product> {
-        ListProduct::build(self.0.into_iter(), p)
+        ListItem::build_list(self.0.into_iter(), p)
     }
 
     fn update(self, p: &mut Self::Product) {
-        p.update(self.0.into_iter());
+        ListItem::update_list(self.0.into_iter(), p);
+    }
+
+    fn hydrate<'p>(self, p: In<'p, Self::Product>, c: &mut Cursor) -> Out<'p, Self::Product> {
+        ListItem::hydrate_list(self.0.into_iter(), p, c)
+    }
+
+    fn render_html(self, html: &mut Html) {
+        for item in self.0 {
+            item.render_item(html);
+        }
     }
 }
 
@@ -68,6 +211,16 @@ where
     fn update(self, p: &mut Self::Product) {
         p.update(self.0.into_iter());
     }
+
+    fn hydrate<'p>(self, p: In<'p, Self::Product>, c: &mut Cursor) -> Out<'p, Self::Product> {
+        BoundedProduct::hydrate(self.0.into_iter(), p, c)
+    }
+
+    fn render_html(self, html: &mut Html) {
+        for view in self.0.into_iter().take(N) {
+            view.render_html(html);
+        }
+    }
 }
 
 impl<V: View> View for Vec<V> {
@@ -80,6 +233,14 @@ impl<V: View> View for Vec<V> {
     fn update(self, p: &mut Self::Product) {
         List::new(self).update(p);
     }
+
+    fn hydrate<'p>(self, p: In<'p, Self::Product>, c: &mut Cursor) -> Out<'p, Self::Product> {
+        List::new(self).hydrate(p, c)
+    }
+
+    fn render_html(self, html: &mut Html) {
+        List::new(self).render_html(html);
+    }
 }
 
 impl<'a, V> View for &'a [V]
@@ -95,6 +256,14 @@ where
     fn update(self, p: &mut Self::Product) {
         List::new(self).update(p)
     }
+
+    fn hydrate<'p>(self, p: In<'p, Self::Product>, c: &mut Cursor) -> Out<'p, Self::Product> {
+        List::new(self).hydrate(p, c)
+    }
+
+    fn render_html(self, html: &mut Html) {
+        List::new(self).render_html(html)
+    }
 }
 
 impl<V: View, const N: usize> View for [V; N] {
@@ -107,4 +276,12 @@ impl<V: View, const N: usize> View for [V; N] {
     fn update(self, p: &mut Self::Product) {
         List::new_bounded(self).update(p)
     }
+
+    fn hydrate<'p>(self, p: In<'p, Self::Product>, c: &mut Cursor) -> Out<'p, Self::Product> {
+        List::new_bounded(self).hydrate(p, c)
+    }
+
+    fn render_html(self, html: &mut Html) {
+        List::new_bounded::<N>(self).render_html(html)
+    }
 }
@@ -8,6 +8,7 @@ use std::ops::{Deref, DerefMut};
 use web_sys::Node;
 
 use crate::dom::{Anchor, Fragment, FragmentBuilder};
+use crate::hydrate::Cursor;
 use crate::init;
 use crate::internal::{In, Out};
 use crate::{Mountable, View};
@@ -36,6 +37,25 @@ impl<P: Mountable, const N: usize> BoundedProduct<P, N> {
         list
     }
 
+    pub fn hydrate<'p, I>(iter: I, p: In<'p, Self>, c: &mut Cursor) -> Out<'p, Self>
+    where
+        I: Iterator,
+        I::Item: View<Product = P>,
+    {
+        p.in_place(|p| unsafe {
+            let head = c.marker();
+
+            let mut list = init!(p.list @ BoundedVec::new(p));
+
+            list.extend(iter, |view, p| view.hydrate(p, c));
+
+            init!(p.mounted = list.len());
+            init!(p.fragment = FragmentBuilder::adopt(&head, c.marker()));
+
+            Out::from_raw(p)
+        })
+    }
+
     pub fn update<I>(&mut self, mut iter: I)
     where
         I: Iterator,
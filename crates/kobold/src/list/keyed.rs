@@ -0,0 +1,483 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use web_sys::Node;
+
+use crate::dom::{Anchor, Fragment, FragmentBuilder};
+use crate::hydrate::Cursor;
+use crate::internal::{self, In, Out};
+use crate::list::Keyed;
+use crate::{Mountable, View};
+
+pub struct KeyedProduct<K, P: Mountable> {
+    list: Vec<(K, Box<P>)>,
+    fragment: FragmentBuilder,
+}
+
+impl<K, P> KeyedProduct<K, P>
+where
+    K: Hash + Eq + 'static,
+    P: Mountable,
+{
+    pub fn build<I, V>(iter: I, p: In<Self>) -> Out<Self>
+    where
+        I: Iterator<Item = Keyed<K, V>>,
+        V: View<Product = P>,
+    {
+        let fragment = FragmentBuilder::new();
+        let list = iter
+            .map(|Keyed { key, view }| {
+                let built = In::boxed(|p| view.build(p));
+
+                fragment.append(built.js());
+
+                (key, built)
+            })
+            .collect();
+
+        p.put(KeyedProduct { list, fragment })
+    }
+
+    pub fn hydrate<'p, I, V>(iter: I, p: In<'p, Self>, c: &mut Cursor) -> Out<'p, Self>
+    where
+        I: Iterator<Item = Keyed<K, V>>,
+        V: View<Product = P>,
+    {
+        let head = c.marker();
+        let list = iter
+            .map(|Keyed { key, view }| (key, In::boxed(|p| view.hydrate(p, c))))
+            .collect();
+        let tail = c.marker();
+
+        p.put(KeyedProduct {
+            list,
+            fragment: FragmentBuilder::adopt(&head, tail),
+        })
+    }
+
+    pub fn update<I, V>(&mut self, iter: I)
+    where
+        I: Iterator<Item = Keyed<K, V>>,
+        V: View<Product = P>,
+    {
+        reconcile(
+            &mut self.list,
+            iter.map(|Keyed { key, view }| (key, view)),
+            &mut &self.fragment,
+            |view, product| view.update(product),
+            |view| In::boxed(|p| view.build(p)),
+        );
+    }
+}
+
+/// DOM operations performed by [`reconcile`] on the items of the list.
+trait Reorder<T> {
+    fn unmount(&mut self, item: &T);
+
+    /// Move a mounted `item` in front of `anchor`, or to the end of the list if `anchor` is `None`.
+    fn relocate(&mut self, item: &T, anchor: Option<&T>);
+
+    /// Insert a new `item` in front of `anchor`, or at the end of the list if `anchor` is `None`.
+    fn insert(&mut self, item: &T, anchor: Option<&T>);
+}
+
+impl<P: Mountable> Reorder<Box<P>> for &FragmentBuilder {
+    fn unmount(&mut self, item: &Box<P>) {
+        item.unmount();
+    }
+
+    // Existing products are moved without being unmounted so that
+    // their nodes never leave the document (preserving focus etc.)
+    fn relocate(&mut self, item: &Box<P>, anchor: Option<&Box<P>>) {
+        match anchor {
+            Some(anchor) => internal::move_before(anchor.js(), item.js()),
+            None => self.move_to_end(item.js()),
+        }
+    }
+
+    fn insert(&mut self, item: &Box<P>, anchor: Option<&Box<P>>) {
+        match anchor {
+            Some(anchor) => internal::insert_before(anchor.js(), item.js()),
+            None => self.append(item.js()),
+        }
+    }
+}
+
+/// Match `new` items to the `list` by their keys, updating matched items in place,
+/// building new ones, and moving them all into the order of `new`.
+fn reconcile<K, T, N>(
+    list: &mut Vec<(K, T)>,
+    new: impl Iterator<Item = (K, N)>,
+    dom: &mut impl Reorder<T>,
+    mut update: impl FnMut(N, &mut T),
+    mut build: impl FnMut(N) -> T,
+) where
+    K: Hash + Eq,
+{
+    let mut old = HashMap::with_capacity(list.len());
+
+    for (idx, (key, item)) in list.drain(..).enumerate() {
+        // Only the last of the duplicate keys can be matched again
+        if let Some((_, duplicate)) = old.insert(key, (idx, item)) {
+            dom.unmount(&duplicate);
+        }
+    }
+
+    let mut positions = Vec::with_capacity(old.len());
+
+    for (key, new) in new {
+        match old.remove(&key) {
+            Some((idx, mut item)) => {
+                update(new, &mut item);
+
+                positions.push(Some(idx));
+                list.push((key, item));
+            }
+            None => {
+                positions.push(None);
+                list.push((key, build(new)));
+            }
+        }
+    }
+
+    for (_, item) in old.values() {
+        dom.unmount(item);
+    }
+
+    let stable = longest_increasing(&positions);
+    let mut anchor: Option<&T> = None;
+
+    // Walk backwards so that every item can be inserted in front
+    // of its successor, which is already in its final position.
+    for ((_, item), (position, stable)) in list.iter().zip(positions.iter().zip(stable)).rev() {
+        if !stable {
+            match position {
+                Some(_) => dom.relocate(item, anchor),
+                None => dom.insert(item, anchor),
+            }
+        }
+        anchor = Some(item);
+    }
+}
+
+/// Marks the longest increasing subsequence of old positions: products
+/// on it can stay where they are, while all others are moved around them.
+fn longest_increasing(positions: &[Option<usize>]) -> Vec<bool> {
+    // Old position and index of the smallest tail for each subsequence length
+    let mut tails: Vec<(usize, usize)> = Vec::new();
+    let mut prev = vec![usize::MAX; positions.len()];
+
+    for (idx, position) in positions.iter().enumerate() {
+        let Some(position) = *position else {
+            continue;
+        };
+
+        let len = tails.partition_point(|&(tail, _)| tail < position);
+
+        if let Some(&(_, before)) = len.checked_sub(1).and_then(|len| tails.get(len)) {
+            prev[idx] = before;
+        }
+        if len == tails.len() {
+            tails.push((position, idx));
+        } else {
+            tails[len] = (position, idx);
+        }
+    }
+
+    let mut stable = vec![false; positions.len()];
+    let mut idx = tails.last().map_or(usize::MAX, |&(_, idx)| idx);
+
+    while let Some(flag) = stable.get_mut(idx) {
+        *flag = true;
+        idx = prev[idx];
+    }
+
+    stable
+}
+
+impl<K, P> Anchor for KeyedProduct<K, P>
+where
+    K: 'static,
+    P: Mountable,
+{
+    type Js = Node;
+    type Target = Fragment;
+
+    fn anchor(&self) -> &Fragment {
+        &self.fragment
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{longest_increasing, reconcile, Reorder};
+
+    /// Children of the list in the DOM, identified by ids of the items.
+    #[derive(Default)]
+    struct Dom {
+        nodes: Vec<u32>,
+        unmounted: Vec<u32>,
+        relocated: usize,
+        built: u32,
+    }
+
+    impl Dom {
+        fn index(&self, item: &u32) -> Option<usize> {
+            self.nodes.iter().position(|node| node == item)
+        }
+
+        fn place(&mut self, item: u32, anchor: Option<&u32>) {
+            let idx = match anchor {
+                Some(anchor) => self.index(anchor).expect("anchor is not mounted"),
+                None => self.nodes.len(),
+            };
+            self.nodes.insert(idx, item);
+        }
+
+        /// Render `keys` into the `list`, returning keys in the order of the DOM nodes.
+        fn render(&mut self, list: &mut Vec<(char, u32)>, keys: &str) -> String {
+            let mut built = self.built;
+
+            reconcile(
+                list,
+                keys.chars().map(|key| (key, ())),
+                self,
+                |(), _| {},
+                |()| {
+                    built += 1;
+                    built
+                },
+            );
+            self.built = built;
+
+            self.nodes
+                .iter()
+                .map(|node| list.iter().find(|(_, item)| item == node).unwrap().0)
+                .collect()
+        }
+    }
+
+    impl Reorder<u32> for Dom {
+        fn unmount(&mut self, item: &u32) {
+            let idx = self.index(item).expect("item is not mounted");
+
+            self.unmounted.push(self.nodes.remove(idx));
+        }
+
+        fn relocate(&mut self, item: &u32, anchor: Option<&u32>) {
+            let idx = self.index(item).expect("relocated item is not mounted");
+
+            self.nodes.remove(idx);
+            self.place(*item, anchor);
+            self.relocated += 1;
+        }
+
+        fn insert(&mut self, item: &u32, anchor: Option<&u32>) {
+            assert_eq!(self.index(item), None, "inserted item is already mounted");
+
+            self.place(*item, anchor);
+        }
+    }
+
+    fn ids(list: &[(char, u32)], keys: &str) -> Vec<u32> {
+        keys.chars()
+            .map(|key| list.iter().find(|(k, _)| *k == key).unwrap().1)
+            .collect()
+    }
+
+    #[test]
+    fn shuffle() {
+        let mut dom = Dom::default();
+        let mut list = Vec::new();
+
+        assert_eq!(dom.render(&mut list, "abcde"), "abcde");
+
+        let before = ids(&list, "abcde");
+
+        assert_eq!(dom.render(&mut list, "ecabd"), "ecabd");
+
+        // Same nodes, only two of them had to move
+        assert_eq!(ids(&list, "abcde"), before);
+        assert_eq!(dom.relocated, 2);
+        assert_eq!(dom.unmounted, []);
+
+        assert_eq!(dom.render(&mut list, "dbaec"), "dbaec");
+        assert_eq!(ids(&list, "abcde"), before);
+        assert_eq!(dom.unmounted, []);
+    }
+
+    #[test]
+    fn insertions() {
+        let mut dom = Dom::default();
+        let mut list = Vec::new();
+
+        dom.render(&mut list, "bd");
+
+        let before = ids(&list, "bd");
+
+        assert_eq!(dom.render(&mut list, "abcde"), "abcde");
+        assert_eq!(ids(&list, "bd"), before);
+        assert_eq!(dom.relocated, 0);
+        assert_eq!(dom.built, 5);
+    }
+
+    #[test]
+    fn removals() {
+        let mut dom = Dom::default();
+        let mut list = Vec::new();
+
+        dom.render(&mut list, "abcde");
+
+        let removed = ids(&list, "ace");
+        let kept = ids(&list, "bd");
+
+        assert_eq!(dom.render(&mut list, "db"), "db");
+        assert_eq!(ids(&list, "db"), [kept[1], kept[0]]);
+        assert_eq!(dom.relocated, 1);
+
+        dom.unmounted.sort();
+        assert_eq!(dom.unmounted, removed);
+    }
+
+    #[test]
+    fn duplicate_keys() {
+        let mut dom = Dom::default();
+        let mut list = Vec::new();
+
+        assert_eq!(dom.render(&mut list, "aba"), "aba");
+
+        let first = list[0].1;
+        let last = list[2].1;
+
+        // Only the last `a` is matched again, the other one is unmounted
+        assert_eq!(dom.render(&mut list, "ab"), "ab");
+        assert_eq!(dom.unmounted, [first]);
+        assert_eq!(list[0].1, last);
+
+        assert_eq!(dom.render(&mut list, "bab"), "bab");
+        assert_eq!(dom.nodes.len(), 3);
+    }
+
+    #[test]
+    fn unchanged_order_is_stable() {
+        let positions = [Some(0), Some(1), Some(2), Some(3)];
+
+        assert_eq!(longest_increasing(&positions), [true; 4]);
+    }
+
+    #[test]
+    fn moved_to_front() {
+        let positions = [Some(3), Some(0), Some(1), Some(2)];
+
+        assert_eq!(longest_increasing(&positions), [false, true, true, true]);
+    }
+
+    #[test]
+    fn swapped() {
+        let positions = [Some(0), Some(3), Some(2), Some(1), Some(4)];
+
+        let stable = longest_increasing(&positions);
+
+        assert_eq!(stable.iter().filter(|s| **s).count(), 3);
+        assert_eq!((stable[0], stable[4]), (true, true));
+    }
+
+    #[test]
+    fn new_items_are_never_stable() {
+        let positions = [None, Some(0), None, Some(2), Some(1), None];
+
+        assert_eq!(
+            longest_increasing(&positions),
+            [false, true, false, false, true, false]
+        );
+    }
+
+    #[test]
+    fn reversed() {
+        let positions = [Some(2), Some(1), Some(0)];
+
+        assert_eq!(longest_increasing(&positions), [false, false, true]);
+    }
+
+    /// Run with `wasm-pack test --headless --chrome`
+    #[cfg(target_arch = "wasm32")]
+    mod browser {
+        use wasm_bindgen::JsCast;
+        use wasm_bindgen_test::{wasm_bindgen_test, wasm_bindgen_test_configure};
+        use web_sys::Node;
+
+        use crate::internal::In;
+        use crate::keywords::r#for;
+        use crate::list::keyed;
+        use crate::{Mountable, View};
+
+        wasm_bindgen_test_configure!(run_in_browser);
+
+        fn list(keys: &'static [&'static str]) -> impl View {
+            r#for(keys.iter().map(|&key| keyed(key, key)))
+        }
+
+        fn find(parent: &Node, text: &str) -> Option<Node> {
+            let mut next = parent.first_child();
+
+            while let Some(node) = next {
+                if node.node_value().as_deref() == Some(text) {
+                    return Some(node);
+                }
+                next = node.next_sibling();
+            }
+            None
+        }
+
+        fn reorder(connected: bool) {
+            let document = web_sys::window().unwrap().document().unwrap();
+            let parent: Node = document.create_element("div").unwrap().into();
+
+            // Detached nodes can't use `moveBefore`, covering the `insertBefore` fallback
+            if connected {
+                document.body().unwrap().append_child(&parent).unwrap();
+            }
+
+            let mut product = In::boxed(|p| list(&["a", "b", "c", "d", "e"]).build(p));
+
+            parent.append_child(product.js().unchecked_ref()).unwrap();
+
+            let before: Vec<Node> = ["a", "b", "c", "e"]
+                .iter()
+                .map(|text| find(&parent, text).unwrap())
+                .collect();
+
+            list(&["e", "c", "a", "b", "x"]).update(&mut product);
+
+            assert_eq!(parent.text_content().unwrap(), "ecabx");
+            assert_eq!(find(&parent, "d"), None);
+
+            for (text, node) in ["a", "b", "c", "e"].iter().zip(&before) {
+                assert!(find(&parent, text).unwrap().is_same_node(Some(node)));
+            }
+
+            list(&["x", "b"]).update(&mut product);
+
+            assert_eq!(parent.text_content().unwrap(), "xb");
+            assert!(find(&parent, "b").unwrap().is_same_node(Some(&before[1])));
+
+            if connected {
+                parent.unchecked_into::<web_sys::Element>().remove();
+            }
+        }
+
+        #[wasm_bindgen_test]
+        fn reorder_connected() {
+            reorder(true);
+        }
+
+        #[wasm_bindgen_test]
+        fn reorder_detached() {
+            reorder(false);
+        }
+    }
+}
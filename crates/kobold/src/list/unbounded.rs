@@ -7,6 +7,7 @@
 use web_sys::Node;
 
 use crate::dom::{Anchor, Fragment, FragmentBuilder};
+use crate::hydrate::Cursor;
 use crate::internal::{In, Out};
 use crate::{Mountable, View};
 
@@ -32,6 +33,22 @@ impl<P: Mountable> ListProduct<P> {
         list
     }
 
+    pub fn hydrate<'p, I>(iter: I, p: In<'p, Self>, c: &mut Cursor) -> Out<'p, Self>
+    where
+        I: Iterator,
+        I::Item: View<Product = P>,
+    {
+        let head = c.marker();
+        let list: Vec<_> = iter.map(|view| In::boxed(|p| view.hydrate(p, c))).collect();
+        let tail = c.marker();
+
+        p.put(ListProduct {
+            mounted: list.len(),
+            list,
+            fragment: FragmentBuilder::adopt(&head, tail),
+        })
+    }
+
     pub fn update<I>(&mut self, mut iter: I)
     where
         I: Iterator,
@@ -0,0 +1,212 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! # Rendering views elsewhere in the DOM
+//!
+//! Modals, tooltips and dropdowns often need to escape their parent elements, for example
+//! when those have `overflow: hidden` set. A [`portal`] renders its view into a different
+//! element, while the view remains part of the parent's view tree:
+//!
+//! ```
+//! use kobold::prelude::*;
+//! use kobold::portal::portal;
+//!
+//! #[component]
+//! fn modal(open: bool, title: &str) -> impl View + '_ {
+//!     open.then(move || {
+//!         portal("body", view! {
+//!             <div.modal>
+//!                 <h2>{ ref title }</h2>
+//!             </div>
+//!         })
+//!     })
+//! }
+//! # fn main() {}
+//! ```
+//!
+//! Updates, event handlers and state flow into the portal's view the same as they would
+//! for any other child view. The view is removed from the target element when the portal is
+//! unmounted or dropped, such as when a list shrinks, and appended to the target element again
+//! the next time the portal is updated.
+
+use std::cell::Cell;
+
+use wasm_bindgen::JsValue;
+use web_sys::Node;
+
+use crate::dom::{MountTarget, Mountable};
+use crate::hydrate::Cursor;
+use crate::internal::{self, empty_node, In, Out};
+use crate::ssr::Html;
+use crate::{init, View};
+
+/// Create a [`View`] that renders `view` at the end of the `target` element instead of
+/// its own location, see the [module documentation](self).
+///
+/// The `target` is resolved once when the portal is built, changing it in updates has no effect.
+///
+/// When rendered to HTML the portal renders nothing, as the target element might not have been
+/// rendered yet. When hydrating, the view is built fresh and appended to the target.
+pub const fn portal<T, V>(target: T, view: V) -> Portal<T, V>
+where
+    T: MountTarget,
+    V: View,
+{
+    Portal { target, view }
+}
+
+pub struct Portal<T, V> {
+    target: T,
+    view: V,
+}
+
+pub struct PortalProduct<P: Mountable> {
+    /// Empty node at the location of the portal in its parent.
+    marker: Node,
+    target: Node,
+    product: P,
+    /// Whether the view has been removed from the target by unmounting the portal.
+    detached: Cell<bool>,
+}
+
+impl<T, V> View for Portal<T, V>
+where
+    T: MountTarget,
+    V: View,
+{
+    type Product = PortalProduct<V::Product>;
+
+    fn build(self, p: In<Self::Product>) -> Out<Self::Product> {
+        let target = self.target.target();
+
+        p.in_place(|p| unsafe {
+            init!(p.marker = empty_node());
+            init!(p.detached = Cell::new(false));
+            let product = init!(p.product @ self.view.build(p));
+
+            internal::obj(&target).append(product.js());
+            init!(p.target = target);
+
+            Out::from_raw(p)
+        })
+    }
+
+    fn update(self, p: &mut Self::Product) {
+        self.view.update(&mut p.product);
+
+        if p.detached.replace(false) {
+            internal::obj(&p.target).append(p.product.js());
+        }
+    }
+
+    fn hydrate<'p>(self, p: In<'p, Self::Product>, c: &mut Cursor) -> Out<'p, Self::Product> {
+        let target = self.target.target();
+
+        p.in_place(|p| unsafe {
+            init!(p.marker = c.marker());
+            init!(p.detached = Cell::new(false));
+
+            // Nothing was rendered on the server, so there is nothing to adopt
+            let product = init!(p.product @ self.view.build(p));
+
+            internal::obj(&target).append(product.js());
+            init!(p.target = target);
+
+            Out::from_raw(p)
+        })
+    }
+
+    fn render_html(self, _: &mut Html) {}
+}
+
+impl<P> Mountable for PortalProduct<P>
+where
+    P: Mountable,
+{
+    type Js = Node;
+
+    fn js(&self) -> &JsValue {
+        &self.marker
+    }
+
+    fn unmount(&self) {
+        internal::obj(&self.marker).unmount();
+        self.detach();
+    }
+
+    fn replace_with(&self, new: &JsValue) {
+        internal::obj(&self.marker).replace(new);
+        self.detach();
+    }
+}
+
+impl<P> PortalProduct<P>
+where
+    P: Mountable,
+{
+    fn detach(&self) {
+        if !self.detached.replace(true) {
+            self.product.unmount();
+        }
+    }
+}
+
+impl<P> Drop for PortalProduct<P>
+where
+    P: Mountable,
+{
+    fn drop(&mut self) {
+        self.detach();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn render_nothing_to_html() {
+        let html = crate::render_to_string(portal("body", "content"));
+
+        assert_eq!(html, "");
+    }
+
+    /// Run with `wasm-pack test --headless --chrome`
+    #[cfg(target_arch = "wasm32")]
+    mod browser {
+        use wasm_bindgen::JsCast;
+        use wasm_bindgen_test::{wasm_bindgen_test, wasm_bindgen_test_configure};
+
+        use super::*;
+
+        wasm_bindgen_test_configure!(run_in_browser);
+
+        #[wasm_bindgen_test]
+        fn unmount_without_dropping() {
+            let document = web_sys::window().unwrap().document().unwrap();
+            let target: Node = document.create_element("div").unwrap().into();
+            let parent: Node = document.create_element("div").unwrap().into();
+
+            let mut product = In::boxed(|p| portal(target.clone(), "content").build(p));
+
+            parent.append_child(product.js().unchecked_ref()).unwrap();
+
+            assert_eq!(target.text_content().unwrap(), "content");
+
+            product.unmount();
+
+            assert!(!parent.has_child_nodes());
+            assert_eq!(target.text_content().unwrap(), "");
+
+            parent.append_child(product.js().unchecked_ref()).unwrap();
+            portal(target.clone(), "updated").update(&mut product);
+
+            assert_eq!(target.text_content().unwrap(), "updated");
+
+            drop(product);
+
+            assert_eq!(target.text_content().unwrap(), "");
+        }
+    }
+}
@@ -0,0 +1,274 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! # Server-side rendering
+//!
+//! Any [`View`] can be rendered to an HTML string with [`render_to_string`]. Unlike
+//! [`View::build`] this doesn't touch the DOM nor call into JavaScript, so it can be
+//! used on native targets to pre-render pages for search engines and faster first paint:
+//!
+//! ```
+//! use kobold::prelude::*;
+//!
+//! #[component]
+//! fn hello(name: &str) -> impl View + '_ {
+//!     view! {
+//!         <h1.greeting>"Hello "{ name }"!"</h1>
+//!     }
+//! }
+//!
+//! # fn main() {
+//! let html = kobold::render_to_string(view! {
+//!     <!hello name="<Kobold>">
+//! });
+//!
+//! assert_eq!(html, r#"<h1 class="greeting">Hello &lt;Kobold&gt;!</h1>"#);
+//! # }
+//! ```
+//!
+//! Event listeners are omitted from the output, and handlers passed to
+//! [`View::on_mount`] or [`View::on_render`] are never called since there
+//! is no DOM node to hand over.
+
+use std::fmt::{self, Display, Write};
+
+use crate::View;
+
+/// Render a [`View`] to an HTML string.
+///
+/// See the [module documentation](self) for details.
+pub fn render_to_string(view: impl View) -> String {
+    let mut html = Html::new();
+
+    view.render_html(&mut html);
+
+    html.into_string()
+}
+
+/// Buffer that [`View`]s render their HTML into, see [`View::render_html`].
+#[derive(Default)]
+pub struct Html {
+    buf: String,
+}
+
+impl Html {
+    /// Create a new empty `Html` buffer.
+    pub fn new() -> Self {
+        Html { buf: String::new() }
+    }
+
+    /// Write raw markup into the buffer without escaping it.
+    pub fn raw(&mut self, markup: &str) {
+        self.buf.push_str(markup);
+    }
+
+    /// Write escaped text into the buffer.
+    pub fn text(&mut self, text: impl Display) {
+        let _ = write!(Escape(&mut self.buf), "{text}");
+    }
+
+    /// Write an attribute with an escaped value, with a leading space: ` name="value"`.
+    pub fn attr(&mut self, name: &str, value: impl Display) {
+        let _ = write!(self.buf, " {name}=\"");
+        self.text(value);
+        self.buf.push('"');
+    }
+
+    /// Write a boolean attribute with no value, with a leading space: ` name`.
+    pub fn flag(&mut self, name: &str) {
+        let _ = write!(self.buf, " {name}");
+    }
+
+    /// Write a single class name inside an open `class="..."` attribute,
+    /// separating it from preceding classes with a space.
+    pub fn class(&mut self, class: &str) {
+        if class.is_empty() {
+            return;
+        }
+        if !self.buf.ends_with('"') {
+            self.buf.push(' ');
+        }
+        self.text(class);
+    }
+
+    /// Write a single CSS property inside an open `style="..."` attribute: `name:value;`.
+    ///
+    /// Same as `style.setProperty` in the DOM ignores invalid values, the property is
+    /// skipped if its value could end the declaration early, such as `red; color: blue`.
+    pub fn style(&mut self, property: &str, value: impl Display) {
+        let value = value.to_string();
+
+        if !is_single_value(&value) {
+            return;
+        }
+
+        let _ = write!(self.buf, "{property}:");
+        self.text(value);
+        self.buf.push(';');
+    }
+
+    /// Get the rendered HTML.
+    pub fn into_string(self) -> String {
+        self.buf
+    }
+}
+
+impl AsRef<str> for Html {
+    fn as_ref(&self) -> &str {
+        &self.buf
+    }
+}
+
+/// Check that a CSS value doesn't contain `;`, `{` or `}` outside of strings and comments,
+/// and that all of its strings and comments are terminated.
+fn is_single_value(value: &str) -> bool {
+    let mut chars = value.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            ';' | '{' | '}' => return false,
+            '\\' => {
+                chars.next();
+            }
+            '"' | '\'' => loop {
+                match chars.next() {
+                    Some('\\') => {
+                        chars.next();
+                    }
+                    Some(end) if end == c => break,
+                    Some('\n') | None => return false,
+                    Some(_) => (),
+                }
+            },
+            '/' if chars.as_str().starts_with('*') => match chars.as_str()[1..].find("*/") {
+                Some(end) => chars = chars.as_str()[end + 3..].chars(),
+                None => return false,
+            },
+            _ => (),
+        }
+    }
+
+    true
+}
+
+/// Writer escaping all characters that are meaningful in HTML text or attribute values.
+struct Escape<'a>(&'a mut String);
+
+impl Write for Escape<'_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let mut rest = s;
+
+        while let Some(idx) = rest.find(['&', '<', '>', '"']) {
+            let (clean, tail) = rest.split_at(idx);
+
+            self.0.push_str(clean);
+            self.0.push_str(match tail.as_bytes()[0] {
+                b'&' => "&amp;",
+                b'<' => "&lt;",
+                b'>' => "&gt;",
+                _ => "&quot;",
+            });
+
+            rest = &tail[1..];
+        }
+
+        self.0.push_str(rest);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn escapes_text() {
+        let mut html = Html::new();
+
+        html.text("<b>\"Fish\" & Chips</b>");
+
+        assert_eq!(
+            html.as_ref(),
+            "&lt;b&gt;&quot;Fish&quot; &amp; Chips&lt;/b&gt;"
+        );
+    }
+
+    #[test]
+    fn attributes() {
+        let mut html = Html::new();
+
+        html.raw("<input");
+        html.attr("value", 42);
+        html.attr("title", "\"quoted\"");
+        html.flag("checked");
+        html.raw(">");
+
+        assert_eq!(
+            html.as_ref(),
+            r#"<input value="42" title="&quot;quoted&quot;" checked>"#
+        );
+    }
+
+    #[test]
+    fn classes() {
+        let mut html = Html::new();
+
+        html.raw("<div class=\"");
+        html.class("");
+        html.class("foo");
+        html.class("");
+        html.class("bar");
+        html.raw("\">");
+
+        assert_eq!(html.as_ref(), r#"<div class="foo bar">"#);
+    }
+
+    #[test]
+    fn styles() {
+        let mut html = Html::new();
+
+        html.raw("<div style=\"");
+        html.style("width", "50%");
+        html.style("--accent", "\"red\"");
+        html.style("opacity", 0.5);
+        html.raw("\">");
+
+        assert_eq!(
+            html.as_ref(),
+            r#"<div style="width:50%;--accent:&quot;red&quot;;opacity:0.5;">"#
+        );
+    }
+
+    #[test]
+    fn style_injection() {
+        let mut html = Html::new();
+
+        html.style("color", "red; background: url(evil)");
+        html.style("color", "red } body { color: blue");
+        html.style("font-family", "\"Unterminated");
+        html.style("color", "red /* unterminated");
+        html.style("content", "\"a;b\"");
+        html.style("content", "'it\\'s'");
+        html.style("color", "red /* ; */");
+
+        assert_eq!(
+            html.as_ref(),
+            r#"content:&quot;a;b&quot;;content:'it\'s';color:red /* ; */;"#
+        );
+    }
+
+    #[test]
+    fn rust_views() {
+        assert_eq!(render_to_string("<&>"), "&lt;&amp;&gt;");
+        assert_eq!(render_to_string(42_u64), "42");
+        assert_eq!(render_to_string(u64::MAX), "18446744073709551615");
+        assert_eq!(render_to_string(1.5), "1.5");
+        assert_eq!(render_to_string(false), "false");
+        assert_eq!(render_to_string(Some("foo")), "foo");
+        assert_eq!(render_to_string(None::<&str>), "");
+        assert_eq!(render_to_string([1, 2, 3]), "123");
+        assert_eq!(render_to_string(vec!["a", "b"]), "ab");
+    }
+}
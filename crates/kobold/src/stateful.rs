@@ -18,26 +18,38 @@ use std::rc::Rc;
 use wasm_bindgen::JsValue;
 use web_sys::Node;
 
+use crate::context;
 use crate::dom::Anchor;
+use crate::hydrate::Cursor;
 use crate::internal::{In, Out};
+use crate::ssr::Html;
 use crate::{init, Mountable, View};
 
 mod cell;
+mod derived;
 mod hook;
 mod into_state;
 mod product;
+mod render;
+mod resource;
 mod should_render;
+mod store;
 
 use cell::WithCell;
-use product::{Product, ProductHandler};
+use derived::Derived;
+use product::{Detached, Product, ProductHandler};
 
 pub use hook::{Bound, Hook, Signal};
 pub use into_state::IntoState;
+pub use render::Render;
+pub use resource::{resource, Resource, Status};
 pub use should_render::{ShouldRender, Then};
+pub use store::Store;
 
 #[repr(C)]
 struct Inner<S, P: ?Sized = dyn Product<S>> {
     state: WithCell<S>,
+    derived: Derived,
     prod: UnsafeCell<P>,
 }
 
@@ -105,24 +117,40 @@ impl<S> Inner<S> {
         // can't do that until `CoerceUnsized` is stabilized.
         //
         // <https://github.com/rust-lang/rust/issues/18598>
-        unsafe { (*self.prod.get()).update(Hook::new(self)) }
+        //
+        // No render is in progress at this point either, so none of the
+        // values derived during the previous render are borrowed anymore.
+        unsafe {
+            self.derived.collect();
+            (*self.prod.get()).update(Hook::new(self))
+        }
     }
 }
 
-impl<S, F, V> View for Stateful<S, F>
+impl<S, F, V> Stateful<S, F>
 where
     S: IntoState,
     F: Fn(*const Hook<S::State>) -> V + 'static,
     V: View,
 {
-    type Product = StatefulProduct<S::State>;
-
-    fn build(self, p: In<Self::Product>) -> Out<Self::Product> {
+    fn init<'p, B>(
+        self,
+        p: In<'p, StatefulProduct<S::State>>,
+        build: B,
+    ) -> Out<'p, StatefulProduct<S::State>>
+    where
+        B: FnOnce(V, In<V::Product>) -> Out<V::Product>,
+    {
         let inner = Rc::new(Inner {
             state: WithCell::new(self.state.init()),
+            derived: Derived::new(),
             prod: UnsafeCell::new(MaybeUninit::uninit()),
         });
 
+        // Updates triggered by the state happen outside of the parent views,
+        // they need to restore the context the view was initially rendered with.
+        let ctx = context::current();
+
         // ⚠️ Safety:
         // ==========
         //
@@ -139,8 +167,10 @@ where
         unsafe {
             In::raw((*inner.prod.get()).as_mut_ptr(), |prod| {
                 ProductHandler::build(
-                    move |hook, product: *mut V::Product| (self.render)(hook).update(&mut *product),
-                    view,
+                    move |hook, product: *mut V::Product| {
+                        context::scope(ctx.clone(), || (self.render)(hook).update(&mut *product))
+                    },
+                    move |p| build(view, p),
                     prod,
                 )
             });
@@ -154,6 +184,19 @@ where
             inner: unsafe { inner.into_init() },
         })
     }
+}
+
+impl<S, F, V> View for Stateful<S, F>
+where
+    S: IntoState,
+    F: Fn(*const Hook<S::State>) -> V + 'static,
+    V: View,
+{
+    type Product = StatefulProduct<S::State>;
+
+    fn build(self, p: In<Self::Product>) -> Out<Self::Product> {
+        self.init(p, move |view, p| view.build(p))
+    }
 
     fn update(self, p: &mut Self::Product) {
         p.inner.state.with(|state| {
@@ -162,6 +205,20 @@ where
             }
         })
     }
+
+    fn hydrate<'p>(self, p: In<'p, Self::Product>, c: &mut Cursor) -> Out<'p, Self::Product> {
+        self.init(p, move |view, p| view.hydrate(p, c))
+    }
+
+    fn render_html(self, html: &mut Html) {
+        let inner = Inner {
+            state: WithCell::new(self.state.init()),
+            derived: Derived::new(),
+            prod: UnsafeCell::new(Detached),
+        };
+
+        (self.render)(Hook::new(&inner)).render_html(html)
+    }
 }
 
 impl<S> Mountable for StatefulProduct<S>
@@ -247,4 +304,21 @@ where
     fn update(self, p: &mut Self::Product) {
         self.with_state.update(&mut p.product);
     }
+
+    fn hydrate<'p>(self, p: In<'p, Self::Product>, c: &mut Cursor) -> Out<'p, Self::Product> {
+        p.in_place(|p| unsafe {
+            let product = init!(p.product @ self.with_state.hydrate(p, c));
+            let signal = Signal {
+                weak: Rc::downgrade(&product.inner),
+            };
+
+            init!(p._no_drop = (self.handler)(signal));
+
+            Out::from_raw(p)
+        })
+    }
+
+    fn render_html(self, html: &mut Html) {
+        self.with_state.render_html(html)
+    }
 }
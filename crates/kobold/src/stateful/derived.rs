@@ -0,0 +1,270 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::any::{Any, TypeId};
+use std::cell::UnsafeCell;
+use std::collections::HashMap;
+use std::panic::Location;
+
+use crate::diff::Diff;
+
+struct Entry<M, T> {
+    memo: M,
+    value: T,
+}
+
+/// Identifies a single derived value: the closure computing it, the place it
+/// was called from, and how many times that place was called before it during
+/// current render (so that calls made in a loop don't share an entry).
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+struct Slot {
+    closure: TypeId,
+    caller: &'static Location<'static>,
+    nth: u32,
+}
+
+struct Stored {
+    ptr: *mut dyn Any,
+    /// Whether the entry was used during current render.
+    used: bool,
+}
+
+/// Cache of values derived from the state, see [`Hook::derive`](super::Hook::derive).
+pub struct Derived {
+    entries: UnsafeCell<HashMap<Slot, Stored>>,
+    /// Number of calls from each place during current render.
+    calls: UnsafeCell<HashMap<(TypeId, &'static Location<'static>), u32>>,
+    /// Entries replaced during current render, references to them might
+    /// still be alive until the render is finished.
+    stale: UnsafeCell<Vec<*mut dyn Any>>,
+}
+
+impl Derived {
+    pub fn new() -> Self {
+        Derived {
+            entries: UnsafeCell::new(HashMap::new()),
+            calls: UnsafeCell::new(HashMap::new()),
+            stale: UnsafeCell::new(Vec::new()),
+        }
+    }
+
+    #[track_caller]
+    pub fn get<S, K, C, T>(&self, state: &S, key: K, compute: C) -> &T
+    where
+        K: Diff,
+        C: FnOnce(&S) -> T + 'static,
+        T: 'static,
+    {
+        // ⚠️ Safety:
+        // ==========
+        //
+        // `Derived` is never shared between threads and nothing here can call back into
+        // it, so borrows of the `entries`, `calls` and `stale` maps are not reentrant.
+        //
+        // Entries are never dropped or mutated while references to their values can
+        // still exist: recomputed values are stored in new entries, and the old
+        // entries are moved to `stale`, which is cleared only between renders, same
+        // as entries that weren't used during the last render.
+        //
+        // The same closure called from the same place always derives the same types,
+        // since the types of both the key and the closure are fixed by the call site.
+        unsafe {
+            let slot = self.slot(TypeId::of::<C>(), Location::caller());
+            let entries = &mut *self.entries.get();
+            let stored = entries.get_mut(&slot);
+
+            if let Some(stored) = &stored {
+                debug_assert!((*stored.ptr).is::<Entry<K::Memo, T>>());
+
+                let entry = stored.ptr as *mut Entry<K::Memo, T>;
+
+                if !key.diff(&mut (*entry).memo) {
+                    return &(*entry).value;
+                }
+            }
+
+            let new: *mut Entry<K::Memo, T> = Box::into_raw(Box::new(Entry {
+                memo: key.into_memo(),
+                value: compute(state),
+            }));
+
+            match stored {
+                Some(stored) => (*self.stale.get()).push(std::mem::replace(&mut stored.ptr, new)),
+                None => {
+                    entries.insert(
+                        slot,
+                        Stored {
+                            ptr: new,
+                            used: true,
+                        },
+                    );
+                }
+            }
+
+            &(*new).value
+        }
+    }
+
+    /// Get the slot for the next call from `caller`, marking its entry as used.
+    unsafe fn slot(&self, closure: TypeId, caller: &'static Location<'static>) -> Slot {
+        let count = (*self.calls.get()).entry((closure, caller)).or_insert(0);
+        let slot = Slot {
+            closure,
+            caller,
+            nth: *count,
+        };
+
+        *count += 1;
+
+        if let Some(stored) = (*self.entries.get()).get_mut(&slot) {
+            stored.used = true;
+        }
+
+        slot
+    }
+
+    /// Start a new render, dropping all entries replaced during the last one,
+    /// as well as entries that weren't used by it at all.
+    ///
+    /// # Safety
+    ///
+    /// Must not be called while a render is in progress.
+    pub unsafe fn collect(&self) {
+        (*self.calls.get()).clear();
+
+        for ptr in (*self.stale.get()).drain(..) {
+            drop(Box::from_raw(ptr));
+        }
+
+        (*self.entries.get()).retain(|_, stored| {
+            if !stored.used {
+                drop(Box::from_raw(stored.ptr));
+            }
+            std::mem::replace(&mut stored.used, false)
+        });
+    }
+}
+
+impl Drop for Derived {
+    fn drop(&mut self) {
+        let entries = self.entries.get_mut().drain().map(|(_, stored)| stored.ptr);
+
+        for ptr in entries.chain(self.stale.get_mut().drain(..)) {
+            drop(unsafe { Box::from_raw(ptr) });
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    use super::*;
+
+    static CALLS: AtomicU32 = AtomicU32::new(0);
+    static LOOP_CALLS: AtomicU32 = AtomicU32::new(0);
+
+    fn derive(derived: &Derived, key: u32) -> &String {
+        derived.get(&"computed", key, move |state| {
+            CALLS.fetch_add(1, Ordering::Relaxed);
+
+            format!("{state} {key}")
+        })
+    }
+
+    #[test]
+    fn recompute_on_key_change() {
+        let derived = Derived::new();
+
+        assert_eq!(derive(&derived, 1), "computed 1");
+        unsafe { derived.collect() };
+        let old = derive(&derived, 1);
+        assert_eq!(old, "computed 1");
+        assert_eq!(CALLS.load(Ordering::Relaxed), 1);
+
+        unsafe { derived.collect() };
+        let new = derive(&derived, 2);
+
+        // Old reference is still valid
+        assert_eq!(old, "computed 1");
+        assert_eq!(new, "computed 2");
+        assert_eq!(CALLS.load(Ordering::Relaxed), 2);
+
+        unsafe { derived.collect() };
+
+        assert_eq!(derive(&derived, 2), "computed 2");
+        assert_eq!(CALLS.load(Ordering::Relaxed), 2);
+    }
+
+    fn render(derived: &Derived, keys: &[u32]) -> Vec<String> {
+        unsafe { derived.collect() };
+
+        keys.iter()
+            .map(|&key| {
+                derived.get(&(), key, move |_| {
+                    LOOP_CALLS.fetch_add(1, Ordering::Relaxed);
+
+                    format!("item {key}")
+                })
+            })
+            .cloned()
+            .collect()
+    }
+
+    #[test]
+    fn same_closure_in_a_loop() {
+        let derived = Derived::new();
+
+        assert_eq!(render(&derived, &[1, 2, 3]), ["item 1", "item 2", "item 3"]);
+        assert_eq!(LOOP_CALLS.load(Ordering::Relaxed), 3);
+
+        // Only the changed item is recomputed
+        assert_eq!(render(&derived, &[1, 5, 3]), ["item 1", "item 5", "item 3"]);
+        assert_eq!(LOOP_CALLS.load(Ordering::Relaxed), 4);
+    }
+
+    #[test]
+    fn separate_closures() {
+        let derived = Derived::new();
+
+        let a = derived.get(&(), 1_u32, |_| "a");
+        let b = derived.get(&(), 1_u32, |_| 42_u32);
+
+        assert_eq!((*a, *b), ("a", 42));
+    }
+
+    static DROPS: AtomicU32 = AtomicU32::new(0);
+
+    struct Counted;
+
+    impl Drop for Counted {
+        fn drop(&mut self) {
+            DROPS.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    fn render_counted(derived: &Derived, len: u32) {
+        unsafe { derived.collect() };
+
+        for key in 0..len {
+            derived.get(&(), key, |_| Counted);
+        }
+    }
+
+    #[test]
+    fn free_unused_entries() {
+        let derived = Derived::new();
+
+        render_counted(&derived, 3);
+        render_counted(&derived, 1);
+        assert_eq!(DROPS.load(Ordering::Relaxed), 0);
+
+        // Entries not used by the last render are freed before the next one
+        render_counted(&derived, 1);
+        assert_eq!(DROPS.load(Ordering::Relaxed), 2);
+
+        drop(derived);
+        assert_eq!(DROPS.load(Ordering::Relaxed), 3);
+    }
+}
@@ -10,8 +10,11 @@ use std::rc::{Rc, Weak};
 
 use wasm_bindgen_futures::spawn_local;
 
+use crate::diff::Diff;
 use crate::event::{EventCast, Listener};
+use crate::hydrate::Cursor;
 use crate::internal::{In, Out};
+use crate::ssr::Html;
 use crate::stateful::{Inner, ShouldRender};
 use crate::View;
 
@@ -93,7 +96,7 @@ impl<S> Hook<S> {
 
     /// Binds a closure to a mutable reference of the state. While this method is public
     /// it's recommended to use the [`bind!`](crate::bind) macro instead.
-    pub fn bind<E, F, O>(&self, callback: F) -> Bound<S, F>
+    pub fn bind<E, F, O>(&self, callback: F) -> Bound<'_, S, F>
     where
         S: 'static,
         E: EventCast,
@@ -134,6 +137,50 @@ impl<S> Hook<S> {
         }
     }
 
+    /// Get a value derived from the state, memoized for as long as the `key` doesn't change.
+    /// The `key` can be any type implementing [`Diff`](crate::diff::Diff).
+    ///
+    /// The `compute` closure is only called on the first render, or when the `key`
+    /// has changed since the last render, which makes this a good fit for expensive
+    /// computations that only depend on a part of the state.
+    ///
+    /// ```
+    /// # use kobold::prelude::*;
+    /// struct Todos {
+    ///     list: Vec<String>,
+    ///     // Bumped every time `list` is mutated
+    ///     revision: u32,
+    /// }
+    ///
+    /// fn example(todos: &Hook<Todos>) -> impl View + '_ {
+    ///     let longest = todos.derive(
+    ///         |todos| todos.revision,
+    ///         |todos| todos.list.iter().map(|todo| todo.len()).max().unwrap_or(0),
+    ///     );
+    ///
+    ///     view! {
+    ///         <p>"Longest todo has "{ *longest }" characters"</p>
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// Derived values are identified by the place `derive` is called from, so calls
+    /// from different places never share a value. Calls made from the same place
+    /// multiple times during a render (such as in a loop) are told apart by their order.
+    /// Values that weren't derived during a render are dropped before the next one.
+    #[track_caller]
+    pub fn derive<F, K, C, T>(&self, key: F, compute: C) -> &T
+    where
+        F: FnOnce(&S) -> K,
+        K: Diff,
+        C: FnOnce(&S) -> T + 'static,
+        T: 'static,
+    {
+        let state: &S = self;
+
+        self.inner.derived.get(state, key(state), compute)
+    }
+
     /// Get the value of state if state implements `Copy`. This is equivalent to writing
     /// `**hook` but conveys intent better.
     pub fn get(&self) -> S
@@ -249,6 +296,14 @@ where
     fn update(self, p: &mut Self::Product) {
         (**self).update(p)
     }
+
+    fn hydrate<'p>(self, p: In<'p, Self::Product>, c: &mut Cursor) -> Out<'p, Self::Product> {
+        (**self).hydrate(p, c)
+    }
+
+    fn render_html(self, html: &mut Html) {
+        (**self).render_html(html)
+    }
 }
 
 #[cfg(test)]
@@ -257,6 +312,7 @@ mod test {
     use wasm_bindgen::JsCast;
 
     use crate::stateful::cell::WithCell;
+    use crate::stateful::derived::Derived;
     use crate::stateful::product::ProductHandler;
     use crate::value::TextProduct;
 
@@ -266,6 +322,7 @@ mod test {
     fn bound_callback_is_copy() {
         let inner = Inner {
             state: WithCell::new(0_i32),
+            derived: Derived::new(),
             prod: UnsafeCell::new(ProductHandler::mock(
                 |_, _| {},
                 TextProduct {
@@ -8,7 +8,7 @@ use wasm_bindgen::JsValue;
 
 use crate::internal::{In, Out};
 use crate::stateful::Hook;
-use crate::{init, Mountable, View};
+use crate::{init, Mountable};
 
 pub trait Product<S> {
     fn update(&mut self, hook: &Hook<S>);
@@ -20,6 +20,22 @@ pub trait Product<S> {
     fn replace_with(&self, new: &JsValue);
 }
 
+/// Placeholder [`Product`] for stateful views that are rendered to HTML
+/// and never mounted, see [`View::render_html`].
+pub struct Detached;
+
+impl<S> Product<S> for Detached {
+    fn update(&mut self, _: &Hook<S>) {}
+
+    fn js(&self) -> &JsValue {
+        unreachable!("Detached product is never mounted")
+    }
+
+    fn unmount(&self) {}
+
+    fn replace_with(&self, _: &JsValue) {}
+}
+
 pub struct ProductHandler<S, P, F> {
     updater: F,
     product: P,
@@ -38,13 +54,13 @@ impl<S, P, F> ProductHandler<S, P, F> {
 }
 
 impl<S, P, F> ProductHandler<S, P, F> {
-    pub fn build<V>(updater: F, view: V, p: In<Self>) -> Out<Self>
+    pub fn build<B>(updater: F, build: B, p: In<Self>) -> Out<Self>
     where
-        V: View<Product = P>,
+        B: FnOnce(In<P>) -> Out<P>,
     {
         p.in_place(|p| unsafe {
             init!(p.updater = updater);
-            init!(p.product @ view.build(p));
+            init!(p.product @ build(p));
 
             Out::from_raw(p)
         })
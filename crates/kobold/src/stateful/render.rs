@@ -0,0 +1,45 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use crate::View;
+
+/// Function rendering a [`View`] that can borrow from its argument.
+///
+/// Used with a higher-ranked bound `for<'a> Render<'a, S>`, so that the render function
+/// has to accept a reference with any lifetime and can't hold on to it past the render.
+/// This trait is implemented for all functions and closures returning a [`View`]:
+///
+/// ```
+/// use kobold::prelude::*;
+///
+/// // Views borrowing from the argument need a function...
+/// fn greeting(name: &String) -> impl View + '_ {
+///     view! { <p>"Hello, "{ ref name }"!"</p> }
+/// }
+///
+/// // ...while closures need the type of their argument annotated.
+/// let store = Store::new(String::from("Bob"));
+/// let a = store.subscribe(greeting);
+/// let b = store.subscribe(|name: &String| view! { <p>{ name.len() }</p> });
+/// ```
+pub trait Render<'a, S: 'a> {
+    /// [`View`] returned by this function.
+    type View: View + 'a;
+
+    /// Render the view.
+    fn render(&self, state: &'a S) -> Self::View;
+}
+
+impl<'a, S, F, V> Render<'a, S> for F
+where
+    S: 'a,
+    F: Fn(&'a S) -> V,
+    V: View + 'a,
+{
+    type View = V;
+
+    fn render(&self, state: &'a S) -> V {
+        self(state)
+    }
+}
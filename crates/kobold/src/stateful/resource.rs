@@ -0,0 +1,246 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::future::Future;
+
+use wasm_bindgen_futures::spawn_local;
+
+use crate::diff::Diff;
+use crate::dom::Anchor;
+use crate::hydrate::Cursor;
+use crate::internal::{In, Out};
+use crate::ssr::Html;
+use crate::stateful::{Hook, OnceProduct, Render, Signal, Stateful, StatefulProduct, Then};
+use crate::{init, Mountable, View};
+
+/// Status of a [`resource`], passed to its render function.
+pub enum Status<T, E> {
+    /// The future is still running.
+    Pending,
+    /// The future has resolved with a value.
+    Ready(T),
+    /// The future has resolved with an error.
+    Failed(E),
+}
+
+pub struct ResourceState<T, E> {
+    /// Incremented every time the future is restarted, so that
+    /// completions of stale futures can be ignored.
+    generation: u32,
+    status: Status<T, E>,
+}
+
+/// Create a [`View`] rendering the result of an asynchronous computation.
+///
+/// The `fetch` closure is called with `deps` to create a future, which is spawned
+/// with [`spawn_local`]. The `render` closure is then called with the current [`Status`]
+/// of the future: first [`Pending`](Status::Pending), and then [`Ready`](Status::Ready) or
+/// [`Failed`](Status::Failed) once the future resolves.
+///
+/// Every time the parent view updates with different `deps`, the status goes back to
+/// `Pending` and the future is created again. Results of stale futures are ignored.
+///
+/// ```
+/// use kobold::prelude::*;
+/// use kobold::branching::Branch3;
+/// use kobold::stateful::{resource, Status};
+///
+/// async fn fetch_user(id: u32) -> Result<String, String> {
+///     // Fetch from some API...
+/// #   Ok(format!("User #{id}"))
+/// }
+///
+/// fn user_status(status: &Status<String, String>) -> impl View + '_ {
+///     match status {
+///         Status::Pending => Branch3::A(view! { <p.loading>"Loading..."</p> }),
+///         Status::Ready(name) => Branch3::B(view! { <p>"Hello, "{ ref name }"!"</p> }),
+///         Status::Failed(err) => Branch3::C(view! { <p.error>{ ref err }</p> }),
+///     }
+/// }
+///
+/// #[component]
+/// fn user(id: u32) -> impl View {
+///     resource(id, fetch_user, user_status)
+/// }
+/// # fn main() {}
+/// ```
+///
+/// Same as with [`Store::subscribe`](super::Store::subscribe), the `render` function has to
+/// accept a reference with any lifetime (see [`Render`]), so it can't hold on to the status.
+///
+/// When rendered to HTML the future is never created, and the status is always `Pending`.
+pub fn resource<D, F, Fut, T, E, R>(deps: D, fetch: F, render: R) -> Resource<D, F, R>
+where
+    D: Diff,
+    F: FnOnce(D) -> Fut,
+    Fut: Future<Output = Result<T, E>> + 'static,
+    T: 'static,
+    E: 'static,
+    R: for<'a> Render<'a, Status<T, E>> + 'static,
+{
+    Resource {
+        deps,
+        fetch,
+        render,
+    }
+}
+
+/// Asynchronous [`View`], see [`resource`].
+pub struct Resource<D, F, R> {
+    deps: D,
+    fetch: F,
+    render: R,
+}
+
+type ResourceSignal<T, E> = Signal<ResourceState<T, E>>;
+type ResourceHook<T, E> = *const Hook<ResourceState<T, E>>;
+type RenderedView<R, T, E> = <R as Render<'static, Status<T, E>>>::View;
+
+pub struct ResourceProduct<M, T, E> {
+    memo: M,
+    product: OnceProduct<ResourceState<T, E>, ResourceSignal<T, E>>,
+}
+
+fn into_stateful<T, E, R>(render: R) -> Stateful<fn() -> ResourceState<T, E>, R> {
+    Stateful {
+        state: || ResourceState {
+            generation: 0,
+            status: Status::Pending,
+        },
+        render,
+    }
+}
+
+fn erase<T, E, R>(render: R) -> impl Fn(ResourceHook<T, E>) -> RenderedView<R, T, E>
+where
+    R: for<'a> Render<'a, Status<T, E>> + 'static,
+{
+    // Same as in `stateful`, lifetimes are erased here. `render` has to accept
+    // any lifetime, so it can't keep the reference past the render.
+    move |hook: ResourceHook<T, E>| {
+        let state: &ResourceState<T, E> = unsafe { &*hook };
+
+        render.render(&state.status)
+    }
+}
+
+fn spawn<Fut, T, E>(future: Fut, signal: ResourceSignal<T, E>, generation: u32)
+where
+    Fut: Future<Output = Result<T, E>> + 'static,
+    T: 'static,
+    E: 'static,
+{
+    spawn_local(async move {
+        let result = future.await;
+
+        signal.update(move |state| {
+            if state.generation != generation {
+                return Then::Stop;
+            }
+
+            state.status = match result {
+                Ok(value) => Status::Ready(value),
+                Err(err) => Status::Failed(err),
+            };
+
+            Then::Render
+        });
+    });
+}
+
+impl<D, F, Fut, T, E, R> View for Resource<D, F, R>
+where
+    D: Diff,
+    F: FnOnce(D) -> Fut,
+    Fut: Future<Output = Result<T, E>> + 'static,
+    T: 'static,
+    E: 'static,
+    R: for<'a> Render<'a, Status<T, E>> + 'static,
+{
+    type Product = ResourceProduct<D::Memo, T, E>;
+
+    fn build(self, p: In<Self::Product>) -> Out<Self::Product> {
+        let future = (self.fetch)(self.deps);
+
+        p.in_place(|p| unsafe {
+            init!(p.memo = self.deps.into_memo());
+            init!(p.product @ into_stateful(erase(self.render)).once(move |signal| {
+                spawn(future, signal.clone(), 0);
+                signal
+            }).build(p));
+
+            Out::from_raw(p)
+        })
+    }
+
+    fn update(self, p: &mut Self::Product) {
+        if !self.deps.diff(&mut p.memo) {
+            return;
+        }
+
+        let signal = &p.product._no_drop;
+        let mut generation = 0;
+
+        signal.update(|state| {
+            state.generation = state.generation.wrapping_add(1);
+            state.status = Status::Pending;
+
+            generation = state.generation;
+        });
+
+        spawn((self.fetch)(self.deps), signal.clone(), generation);
+    }
+
+    fn hydrate<'p>(self, p: In<'p, Self::Product>, c: &mut Cursor) -> Out<'p, Self::Product> {
+        let future = (self.fetch)(self.deps);
+
+        p.in_place(|p| unsafe {
+            init!(p.memo = self.deps.into_memo());
+            init!(p.product @ into_stateful(erase(self.render)).once(move |signal| {
+                spawn(future, signal.clone(), 0);
+                signal
+            }).hydrate(p, c));
+
+            Out::from_raw(p)
+        })
+    }
+
+    fn render_html(self, html: &mut Html) {
+        into_stateful(erase(self.render)).render_html(html)
+    }
+}
+
+impl<M, T, E> Anchor for ResourceProduct<M, T, E>
+where
+    M: 'static,
+    T: 'static,
+    E: 'static,
+{
+    type Js = <StatefulProduct<ResourceState<T, E>> as Mountable>::Js;
+    type Target = StatefulProduct<ResourceState<T, E>>;
+
+    fn anchor(&self) -> &Self::Target {
+        &self.product.product
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn render_pending_to_html() {
+        let view = resource(
+            1_u32,
+            |id| async move { Ok::<_, ()>(id) },
+            |status: &Status<u32, ()>| match status {
+                Status::Pending => "pending",
+                Status::Ready(_) => "ready",
+                Status::Failed(_) => "failed",
+            },
+        );
+
+        assert_eq!(crate::render_to_string(view), "pending");
+    }
+}
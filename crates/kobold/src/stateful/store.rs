@@ -0,0 +1,244 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::cell::{Cell, RefCell};
+use std::rc::{Rc, Weak};
+
+use crate::stateful::cell::WithCell;
+use crate::stateful::{Hook, Render, ShouldRender, Signal, Stateful};
+use crate::View;
+
+/// Shared state living outside of any single view, that can be subscribed to by
+/// any number of components.
+///
+/// `Store` is a cheap handle that can be cloned and moved into event handlers.
+/// Updating the state through any of the clones renders all subscribers.
+///
+/// ```no_run
+/// use kobold::prelude::*;
+///
+/// #[component]
+/// fn counter(store: &Store<u32>) -> impl View {
+///     let handle = store.clone();
+///
+///     store.subscribe(move |count: &u32| {
+///         let store = handle.clone();
+///
+///         view! {
+///             <button onclick={move |_| store.update(|count| *count += 1)}>
+///                 "Clicked "{ *count }" times"
+///             </button>
+///         }
+///     })
+/// }
+///
+/// #[component]
+/// fn total(store: &Store<u32>) -> impl View {
+///     store.subscribe(|count: &u32| view! { <p>"Total clicks: "{ *count }</p> })
+/// }
+///
+/// fn main() {
+///     let store = Store::new(0);
+///
+///     kobold::start(view! {
+///         <!counter store={&store}>
+///         <!counter store={&store}>
+///         <!total store={&store}>
+///     });
+/// }
+/// ```
+pub struct Store<S> {
+    inner: Rc<StoreInner<S>>,
+}
+
+/// Render functions of all subscribers, with their subscription ids.
+type Subscribers = RefCell<Vec<(usize, Rc<dyn Fn()>)>>;
+
+struct StoreInner<S> {
+    state: WithCell<S>,
+    subscribers: Subscribers,
+    next_id: Cell<usize>,
+}
+
+/// Removes the subscriber from the [`Store`] once its product is dropped.
+struct Subscription<S> {
+    store: Weak<StoreInner<S>>,
+    id: usize,
+}
+
+impl<S: 'static> Store<S> {
+    /// Create a new `Store` with initial `state`.
+    pub fn new(state: S) -> Self {
+        Store {
+            inner: Rc::new(StoreInner {
+                state: WithCell::new(state),
+                subscribers: RefCell::new(Vec::new()),
+                next_id: Cell::new(0),
+            }),
+        }
+    }
+
+    /// Update the state of this `Store`, and render all subscribers unless
+    /// the `mutator` returns [`Then::Stop`](crate::stateful::Then::Stop).
+    pub fn update<F, O>(&self, mutator: F)
+    where
+        F: FnOnce(&mut S) -> O,
+        O: ShouldRender,
+    {
+        if self.inner.state.with(mutator).should_render() {
+            self.render();
+        }
+    }
+
+    /// Same as [`update`](Store::update), but it never renders the subscribers.
+    pub fn update_silent<F>(&self, mutator: F)
+    where
+        F: FnOnce(&mut S),
+    {
+        self.inner.state.with(mutator);
+    }
+
+    /// Replace the entire state with a new value and render all subscribers.
+    pub fn set(&self, val: S) {
+        self.update(move |s| *s = val);
+    }
+
+    /// Read the current state.
+    pub fn with<F, O>(&self, f: F) -> O
+    where
+        F: FnOnce(&S) -> O,
+        O: 'static,
+    {
+        self.inner.state.with(|state| f(state))
+    }
+
+    /// Create a [`View`] subscribed to this `Store`, rendering the current state
+    /// with `render` every time the state updates.
+    ///
+    /// The subscription ends when the view is unmounted and its product dropped.
+    ///
+    /// The `render` function has to accept a reference with any lifetime (see [`Render`]),
+    /// so it can't hold on to the state past the render:
+    ///
+    /// ```compile_fail,E0521
+    /// use std::cell::Cell;
+    /// use std::rc::Rc;
+    /// use kobold::prelude::*;
+    ///
+    /// let store = Store::new(0_u32);
+    /// let stash: Rc<Cell<Option<&u32>>> = Rc::default();
+    /// let escape = stash.clone();
+    ///
+    /// let view = store.subscribe(move |count: &u32| {
+    ///     escape.set(Some(count));
+    ///
+    ///     view! { <p>{ *count }</p> }
+    /// });
+    /// ```
+    pub fn subscribe<F>(&self, render: F) -> impl View
+    where
+        F: for<'a> Render<'a, S> + 'static,
+    {
+        let store = self.clone();
+        let weak = Rc::downgrade(&self.inner);
+
+        // Same as `stateful`, which can't be used here as it would
+        // make the returned `impl View` borrow the state.
+        Stateful {
+            state: move || store,
+            render: move |hook: *const Hook<Store<S>>| -> <F as Render<'static, S>>::View {
+                // ⚠️ Safety:
+                // ==========
+                //
+                // Subscribers are only rendered outside of the `Store::update` mutator,
+                // so the state is never mutably borrowed while the reference is alive.
+                //
+                // `render` has to accept any lifetime, so it can't keep the reference
+                // around, and the view borrowing it is consumed by the render.
+                let store: &Store<S> = unsafe { &*hook };
+
+                render.render(unsafe { store.inner.state.ref_unchecked() })
+            },
+        }
+        .once(move |signal| Subscription::new(weak, signal))
+    }
+
+    fn render(&self) {
+        // Subscribers can be added or removed while rendering,
+        // so we need to iterate over a copy.
+        let subscribers: Vec<_> = self
+            .inner
+            .subscribers
+            .borrow()
+            .iter()
+            .map(|(_, render)| render.clone())
+            .collect();
+
+        for render in subscribers {
+            render();
+        }
+    }
+}
+
+impl<S: 'static> Subscription<S> {
+    fn new(store: Weak<StoreInner<S>>, signal: Signal<Store<S>>) -> Option<Self> {
+        let inner = store.upgrade()?;
+        let id = inner.next_id.get();
+
+        inner.next_id.set(id + 1);
+        inner.subscribers.borrow_mut().push((
+            id,
+            Rc::new(move || {
+                if let Some(inner) = signal.weak.upgrade() {
+                    inner.update();
+                }
+            }),
+        ));
+
+        Some(Subscription { store, id })
+    }
+}
+
+impl<S> Drop for Subscription<S> {
+    fn drop(&mut self) {
+        if let Some(inner) = self.store.upgrade() {
+            inner
+                .subscribers
+                .borrow_mut()
+                .retain(|(id, _)| *id != self.id);
+        }
+    }
+}
+
+impl<S> Clone for Store<S> {
+    fn clone(&self) -> Self {
+        Store {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::stateful::Then;
+
+    #[test]
+    fn shared_between_clones() {
+        let store = Store::new(1_u32);
+        let clone = store.clone();
+
+        clone.update(|count| *count += 1);
+        store.update(|count| {
+            *count *= 10;
+            Then::Stop
+        });
+
+        assert_eq!(clone.with(|count| *count), 20);
+
+        store.set(5);
+
+        assert_eq!(clone.with(|count| *count), 5);
+    }
+}
@@ -0,0 +1,108 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! # Scoped styles
+//!
+//! The [`css!`](crate::css) macro takes a stylesheet and makes every class name in it
+//! unique by appending a hash of the stylesheet, so that components can't accidentally
+//! style each other. The macro returns a struct with the scoped class names as fields,
+//! which can be used with the class shorthand of the [`view!`](crate::view) macro:
+//!
+//! ```
+//! use kobold::prelude::*;
+//!
+//! #[component]
+//! fn card(title: &str) -> impl View + '_ {
+//!     let styles = css!(r#"
+//!         .card {
+//!             padding: 1em;
+//!             border: 1px solid #ccc;
+//!         }
+//!
+//!         .card:hover .card-title {
+//!             text-decoration: underline;
+//!         }
+//!     "#);
+//!
+//!     view! {
+//!         <div.{styles.card}>
+//!             <h2.{styles.card_title}>{ ref title }</h2>
+//!         </div>
+//!     }
+//! }
+//! # fn main() {
+//! # let html = kobold::render_to_string(view! { <!card title="Hi"> });
+//! # assert!(html.starts_with(r#"<div class="card-"#));
+//! # }
+//! ```
+//!
+//! Dashes in class names are replaced with underscores in field names, so `.card-title`
+//! becomes `styles.card_title`. Only class selectors are scoped, element and id selectors
+//! are left untouched.
+//!
+//! The stylesheet is added to the `<head>` of the document the first time the `css!`
+//! expression is evaluated in the browser. When rendering on the server, the stylesheet
+//! can be included in the page using `styles.stylesheet().css()`.
+//!
+//! ## Inline styles
+//!
+//! Setting the `style` attribute replaces the entire inline style of an element whenever
+//! the value changes. Individual properties, including custom properties, can instead be
+//! set with `style:name={value}`, and each one is diffed and updated on its own, without
+//! touching properties set elsewhere, such as by JavaScript animations:
+//!
+//! ```
+//! use kobold::prelude::*;
+//!
+//! #[component]
+//! fn progress(percent: u8, color: &str) -> impl View + '_ {
+//!     view! {
+//!         <div.progress style:width={format!("{percent}%")} style:--accent={color}>
+//!     }
+//! }
+//! # fn main() {
+//! let html = kobold::render_to_string(view! {
+//!     <!progress percent={40} color="teal">
+//! });
+//!
+//! assert_eq!(html, r#"<div class="progress" style="width:40%;--accent:teal;"></div>"#);
+//! # }
+//! ```
+//!
+//! The `style` attribute can't be combined with `style:` properties on the same element.
+//! Values are set as a single property each, so a value such as `red; color: blue` is
+//! invalid and ignored, both in the browser and when rendering to HTML.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::internal;
+
+/// A stylesheet produced by the [`css!`](crate::css) macro.
+pub struct Stylesheet {
+    css: &'static str,
+    injected: AtomicBool,
+}
+
+impl Stylesheet {
+    #[doc(hidden)]
+    pub const fn new(css: &'static str) -> Self {
+        Stylesheet {
+            css,
+            injected: AtomicBool::new(false),
+        }
+    }
+
+    /// The CSS of this stylesheet, with class names already scoped.
+    pub const fn css(&self) -> &'static str {
+        self.css
+    }
+
+    /// Add this stylesheet to the document, unless it has been added already.
+    /// Does nothing outside of WASM.
+    pub fn inject(&self) {
+        if cfg!(target_arch = "wasm32") && !self.injected.swap(true, Ordering::Relaxed) {
+            internal::inject_style(self.css);
+        }
+    }
+}
@@ -6,27 +6,49 @@ use web_sys::Node;
 
 use crate::diff::{Diff, Ref, VString};
 use crate::dom::{Anchor, Property, TextContent};
+use crate::hydrate::Cursor;
 use crate::internal::{self, In, Out};
+use crate::ssr::Html;
 use crate::View;
 
 /// Value that can be set as a property on DOM node
 pub trait Value<P>: IntoText {
     fn set_prop(self, prop: P, node: &Node);
+
+    /// Render this value of the property as HTML.
+    fn render_prop(self, prop: P, html: &mut Html);
 }
 
 /// Value that can be turned into a DOM `Text` node
 pub trait IntoText {
     fn into_text(self) -> Node;
+
+    /// Adopt a server-rendered `Text` node with this value at the cursor.
+    ///
+    /// The default implementation creates a new `Text` node and inserts it at the cursor.
+    fn hydrate_text(self, c: &mut Cursor) -> Node
+    where
+        Self: Sized,
+    {
+        let node = self.into_text();
+
+        c.insert(&node);
+        node
+    }
 }
 
 macro_rules! impl_text {
-    ($($util:ident [$($ty:ty),*])*) => {
+    ($($util:ident $hydrate:ident [$($ty:ty),*])*) => {
         $(
             $(
                 impl IntoText for $ty {
                     fn into_text(self) -> Node {
                         internal::$util(self as _)
                     }
+
+                    fn hydrate_text(self, c: &mut Cursor) -> Node {
+                        $hydrate(c, self as _)
+                    }
                 }
             )*
         )*
@@ -34,9 +56,21 @@ macro_rules! impl_text {
 }
 
 impl_text! {
-    text_node [&str, &String, &Ref<str>, &VString]
-    text_node_num [i8, i16, i32, isize, u8, u16, u32, usize, f32, f64]
-    text_node_bool [bool]
+    text_node hydrate_str [&str, &String, &Ref<str>, &VString]
+    text_node_num hydrate_num [i8, i16, i32, isize, u8, u16, u32, usize, f32, f64]
+    text_node_bool hydrate_bool [bool]
+}
+
+fn hydrate_str(c: &mut Cursor, text: &str) -> Node {
+    c.text(text)
+}
+
+fn hydrate_num(c: &mut Cursor, num: f64) -> Node {
+    c.text(&num.to_string())
+}
+
+fn hydrate_bool(c: &mut Cursor, b: bool) -> Node {
+    c.text(if b { "true" } else { "false" })
 }
 
 macro_rules! impl_value {
@@ -49,6 +83,10 @@ macro_rules! impl_value {
                 fn set_prop(self, prop: P, node: &Node) {
                     prop.set(node, self as _);
                 }
+
+                fn render_prop(self, prop: P, html: &mut Html) {
+                    prop.render_html(html, self as _);
+                }
             }
         )*
     };
@@ -87,6 +125,16 @@ impl View for String {
             p.memo.set_prop(TextContent, &p.node);
         }
     }
+
+    fn hydrate<'p>(self, p: In<'p, Self::Product>, c: &mut Cursor) -> Out<'p, Self::Product> {
+        let node = self.as_str().hydrate_text(c);
+
+        p.put(TextProduct { memo: self, node })
+    }
+
+    fn render_html(self, html: &mut Html) {
+        html.text(self);
+    }
 }
 
 /// A helper trait describing integers that might not fit in the JavaScript
@@ -120,6 +168,13 @@ macro_rules! large_int {
                         Err(_) => self.stringify(|s| prop.set(el, s)),
                     }
                 }
+
+                fn render_prop(self, prop: P, html: &mut Html) {
+                    match <$d>::try_from(self) {
+                        Ok(int) => prop.render_html(html, int as f64),
+                        Err(_) => self.stringify(|s| prop.render_html(html, s)),
+                    }
+                }
             }
 
             impl IntoText for $t {
@@ -129,6 +184,13 @@ macro_rules! large_int {
                         Err(_) => self.stringify(internal::text_node),
                     }
                 }
+
+                fn hydrate_text(self, c: &mut Cursor) -> Node {
+                    match <$d>::try_from(self) {
+                        Ok(downcast) => downcast.hydrate_text(c),
+                        Err(_) => self.stringify(|s| c.text(s)),
+                    }
+                }
             }
         )*
     };
@@ -154,6 +216,17 @@ macro_rules! impl_text_view {
                         self.set_prop(TextContent, &p.node);
                     }
                 }
+
+                fn hydrate<'p>(self, p: In<'p, Self::Product>, c: &mut Cursor) -> Out<'p, Self::Product> {
+                    p.put(TextProduct {
+                        memo: self.into_memo(),
+                        node: self.hydrate_text(c),
+                    })
+                }
+
+                fn render_html(self, html: &mut Html) {
+                    self.render_prop(TextContent, html);
+                }
             }
         )*
     };
@@ -172,6 +245,14 @@ impl<'a> View for &&'a str {
     fn update(self, p: &mut Self::Product) {
         (*self).update(p)
     }
+
+    fn hydrate<'p>(self, p: In<'p, Self::Product>, c: &mut Cursor) -> Out<'p, Self::Product> {
+        (*self).hydrate(p, c)
+    }
+
+    fn render_html(self, html: &mut Html) {
+        (*self).render_html(html)
+    }
 }
 
 macro_rules! impl_ref_view {
@@ -187,6 +268,14 @@ macro_rules! impl_ref_view {
                 fn update(self, p: &mut Self::Product) {
                     (*self).update(p)
                 }
+
+                fn hydrate<'p>(self, p: In<'p, Self::Product>, c: &mut Cursor) -> Out<'p, Self::Product> {
+                    (*self).hydrate(p, c)
+                }
+
+                fn render_html(self, html: &mut Html) {
+                    (*self).render_html(html)
+                }
             }
         )*
     };
@@ -6,7 +6,7 @@ use std::cell::Cell;
 use std::fmt::{self, Debug};
 use std::rc::Rc;
 
-use tokens::{Delimiter, Span, TokenStream, TokenTree};
+use tokens::{Delimiter, Span, TokenStream};
 
 #[derive(Default, Debug)]
 pub struct Scope {
@@ -103,19 +103,3 @@ impl Debug for Nested {
         }
     }
 }
-
-pub struct Return {
-    pub ret: TokenTree,
-    pub tokens: TokenStream,
-}
-
-impl Debug for Return {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "return {}", self.tokens)
-    }
-}
-
-#[derive(Debug)]
-pub struct Branch {
-    pub code: Vec<Code>,
-}
@@ -30,7 +30,10 @@ pub fn parse(stream: TokenStream) -> Result<TokenStream, ParseError> {
             #[wasm_bindgen(js_name = \"{fn_name}\")]\
             pub fn t(node: &::kobold::reexport::web_sys::Node, on: bool);\
         }}"),
-        call("::kobold::attribute::StaticClass::new", ("t,", condition)),
+        call(
+            "::kobold::attribute::StaticClass::new",
+            (string(class), ",t,", condition),
+        ),
     )).tokenize();
 
     // panic!("tokens: {}", tokens);
@@ -0,0 +1,359 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! The `css!` macro: scoping class names of a stylesheet to the component it's declared in.
+
+use std::fmt::Write;
+use std::hash::Hasher;
+
+use fnv::FnvHasher;
+use tokens::{Ident, Literal, Span, TokenStream};
+
+use crate::parse::prelude::*;
+use crate::tokenize::prelude::*;
+
+/// At-rules containing nested rules, as opposed to declarations.
+const NESTED_RULES: &[&str] = &[
+    "media",
+    "supports",
+    "container",
+    "layer",
+    "document",
+    "scope",
+];
+
+pub fn parse(stream: TokenStream) -> Result<TokenStream, ParseError> {
+    let mut stream = stream.parse_stream();
+
+    let lit: Literal = match stream.next() {
+        Some(tokens::TokenTree::Literal(lit)) => lit,
+        tt => return Err(ParseError::new("Expected a string literal with CSS", tt)),
+    };
+
+    stream.parse::<()>()?;
+
+    let span = lit.span();
+    let source = unquote(&lit.to_string())
+        .ok_or_else(|| ParseError::new("Expected a string literal with CSS", span))?;
+
+    let mut scoper = Scoper::new(&source);
+
+    scoper
+        .rules(false)
+        .map_err(|msg| ParseError::new(msg, span))?;
+
+    let Scoper { out, classes, .. } = scoper;
+
+    let mut declare = TokenStream::new();
+    let mut init = TokenStream::new();
+
+    for (class, scoped) in &classes {
+        let field = field_name(class, span)?;
+
+        declare.write((field.clone(), ": &'static str,"));
+        init.write((field, ':', string(scoped), ','));
+    }
+
+    let stylesheet = (
+        "static STYLESHEET: ::kobold::style::Stylesheet = ::kobold::style::Stylesheet::new",
+        group('(', string(&out)),
+        ';',
+    );
+
+    Ok(block((
+        stylesheet,
+        "#[derive(Clone, Copy)] #[allow(dead_code, non_snake_case)] struct Styles",
+        block(declare),
+        "impl Styles { pub fn stylesheet(&self) -> &'static ::kobold::style::Stylesheet { &STYLESHEET } }",
+        "STYLESHEET.inject(); Styles",
+        block(init),
+    ))
+    .tokenize())
+}
+
+/// Get the contents of a string literal.
+fn unquote(lit: &str) -> Option<String> {
+    if let Some(raw) = lit.strip_prefix('r') {
+        let hashes = raw.len() - raw.trim_start_matches('#').len();
+        let raw = &raw[hashes..raw.len() - hashes];
+
+        return Some(raw.strip_prefix('"')?.strip_suffix('"')?.into());
+    }
+
+    let mut chars = lit.strip_prefix('"')?.strip_suffix('"')?.chars();
+    let mut out = String::new();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+
+        match chars.next()? {
+            'n' => out.push('\n'),
+            'r' => out.push('\r'),
+            't' => out.push('\t'),
+            '0' => out.push('\0'),
+            '\n' => {
+                // Line continuation skips leading whitespace of the next line
+                let rest = chars.as_str().trim_start();
+
+                chars = rest.chars();
+            }
+            'u' => {
+                let rest = chars.as_str().strip_prefix('{')?;
+                let end = rest.find('}')?;
+
+                out.push(char::from_u32(u32::from_str_radix(&rest[..end], 16).ok()?)?);
+                chars = rest[end + 1..].chars();
+            }
+            'x' => {
+                let rest = chars.as_str();
+
+                out.push(u8::from_str_radix(rest.get(..2)?, 16).ok()? as char);
+                chars = rest[2..].chars();
+            }
+            c => out.push(c),
+        }
+    }
+
+    Some(out)
+}
+
+fn field_name(class: &str, span: Span) -> Result<Ident, ParseError> {
+    let field = class.replace('-', "_");
+
+    if matches!(field.as_str(), "self" | "super" | "crate" | "Self" | "_") {
+        return Err(ParseError::new(
+            format!("Class name `{class}` can't be used as a field name"),
+            span,
+        ));
+    }
+
+    Ok(Ident::new_raw(&field, span))
+}
+
+/// Rewrites class selectors of a stylesheet, collecting the class names along the way.
+struct Scoper<'a> {
+    src: &'a str,
+    pos: usize,
+    out: String,
+    suffix: String,
+    /// Pairs of original and scoped class names, in order of appearance
+    classes: Vec<(String, String)>,
+}
+
+impl<'a> Scoper<'a> {
+    fn new(src: &'a str) -> Self {
+        let mut hasher = FnvHasher::default();
+
+        hasher.write(src.as_bytes());
+
+        Scoper {
+            src,
+            pos: 0,
+            out: String::with_capacity(src.len()),
+            suffix: base62(hasher.finish()),
+            classes: Vec::new(),
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.src[self.pos..].chars().next()
+    }
+
+    /// Copy the next char to the output.
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+
+        self.pos += c.len_utf8();
+        self.out.push(c);
+
+        Some(c)
+    }
+
+    /// Copy the comment or string at the cursor to the output, if there is one.
+    fn skip_literal(&mut self) -> Result<bool, &'static str> {
+        let rest = &self.src[self.pos..];
+
+        let end = if let Some(comment) = rest.strip_prefix("/*") {
+            comment
+                .find("*/")
+                .map(|end| end + 4)
+                .ok_or("Unclosed comment")?
+        } else if let Some(quote) = rest.chars().next().filter(|c| matches!(c, '"' | '\'')) {
+            let mut escaped = false;
+
+            rest.char_indices()
+                .skip(1)
+                .find(|&(_, c)| {
+                    let end = !escaped && c == quote;
+
+                    escaped = !escaped && c == '\\';
+                    end
+                })
+                .map(|(end, _)| end + 1)
+                .ok_or("Unclosed string")?
+        } else {
+            return Ok(false);
+        };
+
+        self.out.push_str(&rest[..end]);
+        self.pos += end;
+
+        Ok(true)
+    }
+
+    /// Process a list of rules, until the closing `}` if `nested`.
+    fn rules(&mut self, nested: bool) -> Result<(), &'static str> {
+        loop {
+            loop {
+                if self.src[self.pos..].starts_with("/*") {
+                    self.skip_literal()?;
+                } else if self.peek().is_some_and(char::is_whitespace) {
+                    self.bump();
+                } else {
+                    break;
+                }
+            }
+
+            match self.peek() {
+                None if nested => return Err("Unclosed {"),
+                None => return Ok(()),
+                Some('}') if nested => {
+                    self.bump();
+                    return Ok(());
+                }
+                Some('}') => return Err("Unexpected }"),
+                Some('@') => self.at_rule()?,
+                Some(_) => {
+                    self.selector()?;
+                    self.declarations()?;
+                }
+            }
+        }
+    }
+
+    fn at_rule(&mut self) -> Result<(), &'static str> {
+        let name_len = self.src[self.pos + 1..]
+            .find(|c: char| !is_ident_char(c))
+            .unwrap_or(self.src.len() - self.pos - 1);
+        let name = &self.src[self.pos + 1..self.pos + 1 + name_len];
+        let nested = NESTED_RULES.contains(&name);
+
+        // Prelude of the at-rule is copied as is
+        loop {
+            if self.skip_literal()? {
+                continue;
+            }
+
+            match self.peek() {
+                Some(';') => {
+                    self.bump();
+                    return Ok(());
+                }
+                Some('{') if nested => {
+                    self.bump();
+                    return self.rules(true);
+                }
+                Some('{') => return self.declarations(),
+                Some(_) => {
+                    self.bump();
+                }
+                None => return Err("Expected { or ; after at-rule"),
+            }
+        }
+    }
+
+    fn selector(&mut self) -> Result<(), &'static str> {
+        let mut attribute = false;
+
+        loop {
+            if self.skip_literal()? {
+                continue;
+            }
+
+            match self.peek() {
+                Some('{') => return Ok(()),
+                Some('[') => attribute = true,
+                Some(']') => attribute = false,
+                Some('.') if !attribute => {
+                    let rest = &self.src[self.pos + 1..];
+                    let len = rest.find(|c: char| !is_ident_char(c)).unwrap_or(rest.len());
+                    let class = &rest[..len];
+
+                    if is_class(class) {
+                        let scoped = format!("{class}-{}", self.suffix);
+
+                        let _ = write!(self.out, ".{scoped}");
+                        self.pos += 1 + len;
+
+                        if !self.classes.iter().any(|(c, _)| c == class) {
+                            self.classes.push((class.into(), scoped));
+                        }
+                        continue;
+                    }
+                }
+                Some(_) => (),
+                None => return Err("Expected { after selector"),
+            }
+
+            self.bump();
+        }
+    }
+
+    /// Copy a `{ ... }` block of declarations as is.
+    fn declarations(&mut self) -> Result<(), &'static str> {
+        let mut depth = 0;
+
+        loop {
+            if self.skip_literal()? {
+                continue;
+            }
+
+            match self.bump() {
+                Some('{') => depth += 1,
+                Some('}') => {
+                    depth -= 1;
+
+                    if depth == 0 {
+                        return Ok(());
+                    }
+                }
+                Some(_) => (),
+                None => return Err("Unclosed {"),
+            }
+        }
+    }
+}
+
+fn is_ident_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '-' || c == '_' || !c.is_ascii()
+}
+
+fn is_class(class: &str) -> bool {
+    let mut chars = class.chars();
+
+    match chars.next() {
+        Some('-') => matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_'),
+        Some(c) => c.is_ascii_alphabetic() || c == '_' || !c.is_ascii(),
+        None => false,
+    }
+}
+
+fn base62(mut n: u64) -> String {
+    const ALPHABET: &[u8; 62] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ\
+                                  abcdefghijklmnopqrstuvwxyz\
+                                  0123456789";
+
+    let mut out = String::new();
+
+    // 6 characters are plenty to avoid collisions between stylesheets
+    for _ in 0..6 {
+        out.push(ALPHABET[(n % 62) as usize] as char);
+        n /= 62;
+    }
+
+    out
+}
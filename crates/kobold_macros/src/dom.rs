@@ -8,6 +8,7 @@ use crate::parse::prelude::*;
 use crate::syntax::CssLabel;
 use crate::tokenize::prelude::*;
 
+mod control_flow;
 mod els;
 mod expression;
 mod shallow;
@@ -39,6 +40,7 @@ pub enum Node {
 }
 
 #[derive(Debug)]
+#[allow(dead_code)]
 pub struct Component {
     pub name: String,
     pub span: Span,
@@ -51,12 +53,22 @@ pub struct Component {
 #[derive(Debug)]
 pub struct HtmlElement {
     pub name: ElementTag,
+    #[allow(dead_code)]
     pub span: Span,
     pub classes: Vec<CssValue>,
+    /// Properties set with `style:name={value}`
+    pub styles: Vec<StyleProperty>,
     pub attributes: Vec<Attribute>,
     pub children: Option<Vec<Node>>,
 }
 
+#[derive(Debug)]
+pub struct StyleProperty {
+    /// Name of the property without the `style:` prefix
+    pub name: CssLabel,
+    pub value: CssValue,
+}
+
 #[derive(Debug)]
 pub struct Property {
     pub name: Ident,
@@ -72,9 +84,65 @@ pub enum CssValue {
 #[derive(Debug)]
 pub struct Attribute {
     pub name: CssLabel,
+    pub options: ListenerOptions,
     pub value: AttributeValue,
 }
 
+/// Options of an event listener, set with modifiers such as `onclick.prevent={...}`
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ListenerOptions {
+    pub capture: bool,
+    pub passive: bool,
+    pub once: bool,
+    pub prevent: bool,
+    pub stop: bool,
+}
+
+impl ListenerOptions {
+    fn set(&mut self, modifier: &Ident) -> Result<(), ParseError> {
+        let flag = modifier.with_str(|m| match m {
+            "capture" => Some(&mut self.capture),
+            "passive" => Some(&mut self.passive),
+            "once" => Some(&mut self.once),
+            "prevent" => Some(&mut self.prevent),
+            "stop" => Some(&mut self.stop),
+            _ => None,
+        });
+
+        match flag {
+            Some(flag) => *flag = true,
+            None => return Err(ParseError::new(
+                "Unknown event modifier, expected one of: capture, passive, once, prevent, stop",
+                modifier.span(),
+            )),
+        }
+
+        if self.passive && self.prevent {
+            return Err(ParseError::new(
+                "Passive listeners can't prevent the default action",
+                modifier.span(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Bit flags of all options, as expected by `Cursor::listen_with`
+    pub fn flags(&self) -> u8 {
+        [
+            self.capture,
+            self.passive,
+            self.once,
+            self.prevent,
+            self.stop,
+        ]
+        .iter()
+        .enumerate()
+        .map(|(bit, &on)| u8::from(on) << bit)
+        .sum()
+    }
+}
+
 #[derive(Debug)]
 pub enum AttributeValue {
     Literal(Literal),
@@ -167,6 +235,7 @@ impl Node {
 
                         attributes.push(Attribute {
                             name,
+                            options: ListenerOptions::default(),
                             value: value.into(),
                         })
                     } else {
@@ -174,8 +243,25 @@ impl Node {
                     }
                 }
 
+                let mut styles = Vec::new();
+                let mut style = None;
+
                 while !content.end() {
-                    let attr: Attribute = content.parse()?;
+                    let mut attr: Attribute = content.parse()?;
+
+                    if let Some(property) = attr.name.label.strip_prefix("style:") {
+                        attr.name.label = property.into();
+
+                        styles.push(StyleProperty {
+                            name: attr.name,
+                            value: CssValue::try_from(attr.value)?,
+                        });
+                        continue;
+                    }
+
+                    if attr.name.label == "style" {
+                        style = Some(attr.name.ident.span());
+                    }
 
                     if attr.name.label == "class" {
                         classes.push(CssValue::try_from(attr.value)?);
@@ -184,6 +270,13 @@ impl Node {
                     }
                 }
 
+                if let (Some(span), false) = (style, styles.is_empty()) {
+                    return Err(ParseError::new(
+                        "The `style` attribute can't be combined with `style:` properties",
+                        span,
+                    ));
+                }
+
                 let children = match children {
                     Children::None => None,
                     Children::Explicit(children) | Children::Implicit(children) => Some(children),
@@ -193,6 +286,7 @@ impl Node {
                     name,
                     span,
                     classes,
+                    styles,
                     attributes,
                     children,
                 }));
@@ -358,6 +452,7 @@ impl Parse for Attribute {
 
             return Ok(Attribute {
                 name,
+                options: ListenerOptions::default(),
                 value: Expression::try_from(expr)?.into(),
             });
         }
@@ -369,21 +464,52 @@ impl Parse for Attribute {
             ));
         }
 
-        let name = stream.parse()?;
+        let mut name: CssLabel = stream.parse()?;
+        let mut options = ListenerOptions::default();
+
+        if stream.allow(':') {
+            name = match name.label.as_str() {
+                "style" => prefixed_name("style:", stream)?,
+                "data" => prefixed_name("data-", stream)?,
+                "aria" => prefixed_name("aria-", stream)?,
+                _ => {
+                    return Err(ParseError::new(
+                        "Unknown attribute prefix, expected one of: style, data, aria",
+                        name.ident.span(),
+                    ))
+                }
+            };
+        }
+
+        while stream.allow_consume('.').is_some() {
+            let modifier: Ident = stream.parse()?;
+
+            if !name.label.starts_with("on") {
+                return Err(ParseError::new(
+                    "Modifiers can only be used on event listeners",
+                    modifier.span(),
+                ));
+            }
+
+            options.set(&modifier)?;
+        }
 
         stream.expect('=')?;
 
         match stream.next() {
             Some(TokenTree::Literal(lit)) => Ok(Attribute {
                 name,
+                options,
                 value: AttributeValue::Literal(lit),
             }),
             Some(TokenTree::Ident(b)) if b.one_of(["true", "false"]) => Ok(Attribute {
                 name,
+                options,
                 value: AttributeValue::Boolean(b),
             }),
             Some(tt) if tt.is('{') => Ok(Attribute {
                 name,
+                options,
                 value: Expression::try_from(tt)?.into(),
             }),
             _ => Err(ParseError::new(
@@ -393,3 +519,26 @@ impl Parse for Attribute {
         }
     }
 }
+
+/// Parse the name following a prefix such as `style:` or `data:`, including CSS custom
+/// properties such as `style:--accent`. The label of the returned name starts with `prefix`.
+fn prefixed_name(prefix: &str, stream: &mut ParseStream) -> Result<CssLabel, ParseError> {
+    let mut label = String::from(prefix);
+
+    stream.expect(':')?;
+
+    while stream.allow_consume('-').is_some() {
+        label.push('-');
+    }
+
+    let name: CssLabel = stream
+        .parse()
+        .map_err(|err| err.msg(format!("Expected a name after `{prefix}`")))?;
+
+    label.push_str(&name.label);
+
+    Ok(CssLabel {
+        label,
+        ident: name.ident,
+    })
+}
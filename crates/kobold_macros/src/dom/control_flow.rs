@@ -0,0 +1,278 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Inline `if` and `match` expressions with markup in their branches, such as
+//! `{ if cond { <p>"yes"</p> } else { <p>"no"</p> } }`, lowered to `BranchN` enums.
+
+use tokens::{Group, Spacing, Span, TokenStream, TokenTree};
+
+use crate::parse::prelude::*;
+use crate::tokenize::prelude::*;
+
+/// Maximum number of branches, matching the largest `BranchN` enum.
+const MAX_BRANCHES: usize = 9;
+
+enum Part {
+    Code(TokenTree),
+    Branch(Body),
+    /// The `{ ... }` block of a `match` containing its arms
+    Arms(Span, Vec<Part>),
+}
+
+struct Body {
+    tokens: TokenStream,
+    span: Span,
+}
+
+impl Body {
+    fn new(tokens: TokenStream, span: Span) -> Self {
+        Body { tokens, span }
+    }
+
+    /// Markup bodies start with a tag, anything else is left as a Rust expression.
+    fn is_markup(&self) -> bool {
+        self.tokens.clone().parse_stream().allow('<')
+    }
+}
+
+/// Lower an `if` or `match` expression to branches, returns `None` if `group` doesn't
+/// contain such expression, or none of its branches contain markup.
+pub fn parse(group: &Group) -> Result<Option<TokenStream>, ParseError> {
+    let mut stream = group.stream().parse_stream();
+    let mut parts = Vec::new();
+
+    let parsed = match stream.peek() {
+        Some(tt) if tt.is("if") => parse_if(&mut stream, &mut parts),
+        Some(tt) if tt.is("match") => parse_match(&mut stream, &mut parts),
+        _ => return Ok(None),
+    };
+
+    let mut branches = 0;
+    let mut markup = false;
+
+    count(&parts, &mut branches, &mut markup);
+
+    // Without markup this is a regular Rust expression, which might not be
+    // just an `if` or `match`, such as `{ if a { 1 } else { 2 } + 3 }`
+    if !markup {
+        return Ok(None);
+    }
+
+    parsed?;
+
+    if branches > MAX_BRANCHES {
+        return Err(ParseError::new(
+            format!("Inline control flow supports at most {MAX_BRANCHES} branches"),
+            group.span(),
+        ));
+    }
+
+    let mut out = TokenStream::new();
+
+    tokenize(parts, branches, &mut 0, &mut out);
+
+    Ok(Some(out))
+}
+
+fn parse_if(stream: &mut ParseStream, parts: &mut Vec<Part>) -> Result<(), ParseError> {
+    loop {
+        // `if` keyword and the condition, up to the block
+        let block = code_until_block(stream, parts)?;
+
+        parts.push(Part::Branch(Body::new(block.stream(), block.span())));
+
+        match stream.next() {
+            // Missing `else` renders nothing
+            None => {
+                parts.push(Part::Code(ident("else").into()));
+                parts.push(Part::Branch(Body::new(
+                    "::kobold::branching::Empty".tokenize(),
+                    block.span(),
+                )));
+
+                return Ok(());
+            }
+            Some(tt) if tt.is("else") => parts.push(Part::Code(tt)),
+            tt => return Err(ParseError::new("Expected `else`", tt)),
+        }
+
+        if stream.allow("if") {
+            continue;
+        }
+
+        let block = match next_block(stream) {
+            Some(block) => block,
+            None => {
+                return Err(ParseError::new(
+                    "Expected `if` or a `{ ... }` block",
+                    stream.next(),
+                ))
+            }
+        };
+
+        parts.push(Part::Branch(Body::new(block.stream(), block.span())));
+
+        return match stream.next() {
+            None => Ok(()),
+            tt => Err(ParseError::new("Unexpected token after `else` block", tt)),
+        };
+    }
+}
+
+fn parse_match(stream: &mut ParseStream, parts: &mut Vec<Part>) -> Result<(), ParseError> {
+    // `match` keyword and the scrutinee, up to the block with arms
+    let block = code_until_block(stream, parts)?;
+
+    let mut arms = Vec::new();
+    let parsed = parse_arms(&block, &mut arms);
+
+    parts.push(Part::Arms(block.span(), arms));
+    parsed?;
+
+    match stream.next() {
+        None => Ok(()),
+        tt => Err(ParseError::new("Unexpected token after `match` block", tt)),
+    }
+}
+
+fn parse_arms(block: &Group, arms: &mut Vec<Part>) -> Result<(), ParseError> {
+    let mut stream = block.stream().parse_stream();
+
+    while !stream.end() {
+        // Pattern and an optional guard, up to `=>`
+        loop {
+            let tt = match stream.next() {
+                Some(tt) => tt,
+                None => return Err(ParseError::new("Expected `=>`", block.span())),
+            };
+            let arrow = tt.is(('=', Spacing::Joint)) && stream.allow('>');
+
+            arms.push(Part::Code(tt));
+
+            if arrow {
+                arms.extend(stream.next().map(Part::Code));
+                break;
+            }
+        }
+
+        let body = match next_block(&mut stream) {
+            Some(group) => Body::new(group.stream(), group.span()),
+            None => {
+                let mut tokens = TokenStream::new();
+                let span = stream.peek().map(TokenTree::span).unwrap_or(block.span());
+
+                while !stream.end() && !ends_arm(&stream) {
+                    tokens.extend(stream.next());
+                }
+
+                Body::new(tokens, span)
+            }
+        };
+
+        arms.push(Part::Branch(body));
+        arms.extend(stream.allow_consume(',').map(Part::Code));
+    }
+
+    Ok(())
+}
+
+/// Check if the `stream` starts with a `,` ending an arm, followed by another arm or nothing.
+///
+/// Commas can also be a part of the arm expression without being wrapped in a group,
+/// such as in `foo::<A, B>()`. Patterns can't contain such commas, so those are followed
+/// by another comma or the end of the arms before the next `=>`.
+fn ends_arm(stream: &ParseStream) -> bool {
+    let mut rest = stream.clone();
+
+    if !rest.next().is(',') {
+        return false;
+    }
+    if rest.end() {
+        return true;
+    }
+
+    while let Some(tt) = rest.next() {
+        if tt.is(',') {
+            return false;
+        }
+        if tt.is(('=', Spacing::Joint)) && rest.allow('>') {
+            return true;
+        }
+    }
+
+    false
+}
+
+fn next_block(stream: &mut ParseStream) -> Option<Group> {
+    match stream.next_if(|tt| tt.is('{')) {
+        Some(TokenTree::Group(group)) => Some(group),
+        _ => None,
+    }
+}
+
+/// Push tokens to `parts` until a `{ ... }` block is found, returning that block.
+fn code_until_block(stream: &mut ParseStream, parts: &mut Vec<Part>) -> Result<Group, ParseError> {
+    loop {
+        if let Some(block) = next_block(stream) {
+            return Ok(block);
+        }
+
+        match stream.next() {
+            Some(tt) => parts.push(Part::Code(tt)),
+            None => return Err(ParseError::new("Expected a `{ ... }` block", None)),
+        }
+    }
+}
+
+fn count(parts: &[Part], branches: &mut usize, markup: &mut bool) {
+    for part in parts {
+        match part {
+            Part::Code(_) => (),
+            Part::Branch(body) => {
+                *branches += 1;
+                *markup |= body.is_markup();
+            }
+            Part::Arms(_, arms) => count(arms, branches, markup),
+        }
+    }
+}
+
+fn tokenize(parts: Vec<Part>, branches: usize, n: &mut u8, out: &mut TokenStream) {
+    for part in parts {
+        match part {
+            Part::Code(tt) => out.write(tt),
+            Part::Branch(body) => {
+                let mut inner = if body.is_markup() {
+                    ("::kobold::view!", block(body.tokens)).tokenize()
+                } else {
+                    block(body.tokens).tokenize()
+                };
+
+                if branches > 1 {
+                    let variant = (b'A' + *n) as char;
+
+                    inner = call(
+                        format_args!("::kobold::branching::Branch{branches}::{variant}"),
+                        inner,
+                    );
+                }
+
+                *n += 1;
+
+                let mut group = group('{', inner);
+                group.set_span(body.span);
+                out.write(group);
+            }
+            Part::Arms(span, arms) => {
+                let mut inner = TokenStream::new();
+
+                tokenize(arms, branches, n, &mut inner);
+
+                let mut group = group('{', inner);
+                group.set_span(span);
+                out.write(group);
+            }
+        }
+    }
+}
@@ -6,12 +6,13 @@ use std::fmt::{self, Debug};
 
 use tokens::{Group, Ident, Span, TokenStream, TokenTree};
 
-use crate::dom::{IteratorExt, Lit, Node, ParseError};
+use crate::dom::{control_flow, IteratorExt, Lit, Node, ParseError};
 use crate::parse::IdentExt;
 use crate::tokenize::prelude::*;
 
 pub struct Expression {
     pub stream: TokenStream,
+    #[allow(dead_code)]
     pub span: Span,
     pub is_static: bool,
 }
@@ -33,7 +34,7 @@ impl TryFrom<TokenTree> for Expression {
 
     fn try_from(tt: TokenTree) -> Result<Self, ParseError> {
         if let TokenTree::Group(group) = tt {
-            return Ok(Expression::try_from(group)?);
+            return Expression::try_from(group);
         }
 
         let span = tt.span();
@@ -51,6 +52,14 @@ impl TryFrom<Group> for Expression {
     type Error = ParseError;
 
     fn try_from(group: Group) -> Result<Self, ParseError> {
+        if let Some(stream) = control_flow::parse(&group)? {
+            return Ok(Expression {
+                stream,
+                span: group.span(),
+                is_static: false,
+            });
+        }
+
         let mut stream = group.stream().parse_stream();
 
         if let Some(TokenTree::Ident(ident)) = stream.peek() {
@@ -83,14 +92,12 @@ impl TryFrom<Group> for Expression {
             if let Some(mut keyword) = keyword {
                 stream.next();
 
-                if keyword == "for" {
-                    if let Some(_) = stream.allow_consume('<') {
-                        let n = stream.expect(Lit)?;
-                        let close = stream.expect('>')?;
+                if keyword == "for" && stream.allow_consume('<').is_some() {
+                    let n = stream.expect(Lit)?;
+                    let close = stream.expect('>')?;
 
-                        keyword = "for_bounded";
-                        invoke = Some(("::<_, ", n, close).tokenize())
-                    }
+                    keyword = "for_bounded";
+                    invoke = Some(("::<_, ", n, close).tokenize())
                 }
                 let keyword = Ident::new_raw(keyword, span);
 
@@ -5,7 +5,7 @@
 use std::fmt::{Debug, Write};
 
 use arrayvec::ArrayString;
-use tokens::{Ident, TokenStream};
+use tokens::{Ident, Literal, TokenStream};
 
 use crate::dom::{Expression, Node};
 use crate::itertools::IteratorExt;
@@ -14,10 +14,12 @@ use crate::tokenize::prelude::*;
 mod component;
 mod element;
 mod fragment;
+mod html;
 mod transient;
 
 pub use element::JsElement;
 pub use fragment::{append, JsFragment};
+pub use html::HtmlCode;
 pub use transient::{Anchor, Field, FieldKind, Hint, Transient};
 pub use transient::{JsArgument, JsFnName, JsFunction, JsString};
 
@@ -48,9 +50,41 @@ pub fn generate(mut nodes: Vec<Node>) -> Transient {
 pub struct Generator {
     names: NameGenerator,
     out: Transient,
+
+    /// Variable name of the element or fragment whose children are being generated,
+    /// `None` for the root of the view
+    parent: Option<Short>,
 }
 
 impl Generator {
+    /// Name of the hydration cursor for the current parent, such as `_e0`
+    fn cursor(&self) -> String {
+        match self.parent {
+            Some(var) => format!("_{var}"),
+            None => "_c".into(),
+        }
+    }
+
+    /// Expression borrowing the hydration cursor for the current parent
+    fn cursor_mut(&self) -> String {
+        match self.parent {
+            Some(var) => format!("&mut _{var}"),
+            None => "&mut *_c".into(),
+        }
+    }
+
+    fn hydrate_field(&mut self, name: Short) {
+        let cursor = self.cursor_mut();
+
+        let _ = write!(
+            self.out.hydrate,
+            "{{\
+                let _c = {cursor};\
+                ::kobold::init!(_p.{name} @ self.{name}.hydrate(_p, _c));\
+            }}"
+        );
+    }
+
     fn add_field(&mut self, value: TokenStream) -> &mut Field {
         let name = self.names.next();
 
@@ -79,6 +113,12 @@ impl Generator {
                 let body = format!("return document.createTextNode({text});\n");
                 let var = self.names.next_el();
 
+                let _ = write!(
+                    self.out.hydrate,
+                    "let _{var} = _c.text({});",
+                    str_lit(&text.0)
+                );
+
                 (var, body, Vec::new(), Anchor::Node)
             }
             DomNode::Element(JsElement {
@@ -132,6 +172,18 @@ impl Generator {
     }
 }
 
+/// Turn a literal into a Rust `&str` literal.
+pub fn str_lit(lit: &Literal) -> String {
+    let lit = lit.to_string();
+
+    match lit.as_bytes() {
+        [b'"', ..] | [b'r', b'"' | b'#', ..] => lit,
+        [b'\'', b'"', b'\''] => r#""\"""#.into(),
+        [b'\'', ..] => format!("\"{}\"", &lit[1..lit.len() - 1]),
+        _ => format!("\"{lit}\""),
+    }
+}
+
 trait IntoGenerator {
     fn into_gen(self, gen: &mut Generator) -> DomNode;
 }
@@ -140,11 +192,28 @@ impl IntoGenerator for Expression {
     fn into_gen(self, gen: &mut Generator) -> DomNode {
         let field = gen.add_field(self.stream);
 
+        let name = field.name;
+
         if self.is_static {
             field.kind = FieldKind::StaticView;
+
+            let cursor = gen.cursor_mut();
+
+            let _ = write!(
+                gen.out.hydrate,
+                "{{\
+                    let _c = {cursor};\
+                    let {name} = std::pin::pin!(std::mem::MaybeUninit::uninit());\
+                    ::kobold::internal::In::pinned({name}, move |_p| self.{name}.hydrate(_p, _c));\
+                }}"
+            );
+        } else {
+            gen.hydrate_field(name);
         }
 
-        DomNode::Variable(field.name)
+        write!(gen.out.html, "self.{name}.render_html(_h);");
+
+        DomNode::Variable(name)
     }
 }
 
@@ -154,7 +223,16 @@ impl IntoGenerator for Node {
             Node::Component(component) => component.into_gen(gen),
             Node::HtmlElement(element) => element.into_gen(gen),
             Node::Expression(expr) => expr.into_gen(gen),
-            Node::Text(lit) => DomNode::TextNode(JsString(lit)),
+            Node::Text(lit) => {
+                write!(gen.out.html, "_h.text({lit});");
+
+                // Text at the root is hydrated when it's hoisted
+                if gen.parent.is_some() {
+                    let _ = write!(gen.out.hydrate, "{}.text({});", gen.cursor(), str_lit(&lit));
+                }
+
+                DomNode::TextNode(JsString(lit))
+            }
         }
     }
 }
@@ -43,6 +43,10 @@ impl IntoGenerator for Component {
 
         gen.out.fields.push(Field::new(name, value));
 
+        gen.hydrate_field(name);
+
+        write!(gen.out.html, "self.{name}.render_html(_h);");
+
         DomNode::Variable(name)
     }
 }
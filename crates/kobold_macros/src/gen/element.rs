@@ -6,10 +6,12 @@ use std::fmt::{Arguments, Write};
 
 use tokens::{Literal, TokenStream};
 
-use crate::dom::{Attribute, AttributeValue, CssValue, ElementTag, HtmlElement};
-use crate::gen::{append, DomNode, Generator, IntoGenerator, JsArgument, Short};
+use crate::dom::{
+    Attribute, AttributeValue, CssValue, ElementTag, HtmlElement, ListenerOptions, StyleProperty,
+};
+use crate::gen::{append, str_lit, DomNode, Generator, IntoGenerator, JsArgument, Short};
 use crate::itertools::IteratorExt as _;
-use crate::parse::IteratorExt as _;
+use crate::parse::{IdentExt as _, IteratorExt as _, TokenTreeExt as _};
 use crate::tokenize::prelude::*;
 
 pub struct JsElement {
@@ -52,10 +54,31 @@ impl IntoGenerator for HtmlElement {
             hoisted: false,
         };
 
+        gen.out.html.raw(&format!("<{}", el.tag));
+
+        let _ = match el.tag.namespace() {
+            Some(ns) => write!(
+                gen.out.hydrate,
+                "let mut _{var} = {}.element_ns(\"{ns}\",\"{}\");",
+                gen.cursor(),
+                el.tag
+            ),
+            None => write!(
+                gen.out.hydrate,
+                "let mut _{var} = {}.element(\"{}\");",
+                gen.cursor(),
+                el.tag
+            ),
+        };
+
         match (self.classes.len(), el.tag.namespace().is_none()) {
             (0, _) => (),
             (1, true) => match self.classes.remove(0) {
-                CssValue::Literal(class) => writeln!(el, "{var}.className={class};"),
+                CssValue::Literal(class) => {
+                    writeln!(el, "{var}.className={class};");
+                    write!(gen.out.html, "_h.attr(\"class\",{class});");
+                    let _ = write!(gen.out.hydrate, "_{var}.class({class});");
+                }
                 CssValue::Expression(expr) => {
                     el.hoisted = true;
 
@@ -63,7 +86,14 @@ impl IntoGenerator for HtmlElement {
                         name: "ClassName",
                         abi: None,
                     };
-                    gen.add_field(expr.stream).attr(el.var, attr, attr.prop());
+                    let prop = attr.prop();
+                    let name = gen
+                        .add_field(expr.stream)
+                        .attr(el.var, attr, prop.clone())
+                        .name;
+
+                    write!(gen.out.html, "self.{name}.render_html({prop},_h);");
+                    hydrate_attr(gen, var, name, &prop);
                 }
             },
             _ => {
@@ -84,31 +114,107 @@ impl IntoGenerator for HtmlElement {
                     abi: None,
                 };
 
+                gen.out.html.raw(" class=\"");
+
                 for class in self.classes {
-                    if let CssValue::Expression(expr) = class {
-                        el.hoisted = true;
-                        gen.add_field(expr.stream).attr(el.var, attr, attr.prop());
+                    match class {
+                        CssValue::Literal(class) => {
+                            write!(gen.out.html, "_h.class({class});");
+                            let _ = write!(gen.out.hydrate, "_{var}.class({class});");
+                        }
+                        CssValue::Expression(expr) => {
+                            el.hoisted = true;
+
+                            let prop = attr.prop();
+                            let name = gen
+                                .add_field(expr.stream)
+                                .attr(el.var, attr, prop.clone())
+                                .name;
+
+                            write!(gen.out.html, "self.{name}.render_html({prop},_h);");
+                            hydrate_attr(gen, var, name, &prop);
+                        }
                     }
                 }
+
+                gen.out.html.raw("\"");
             }
         }
 
-        for Attribute { name, value } in self.attributes {
+        // `innerHTML` has to be rendered as content of the element, after all other attributes
+        let mut inner_html = None;
+
+        for Attribute {
+            name,
+            options,
+            value,
+        } in self.attributes
+        {
+            if let ("ref", AttributeValue::Expression(expr)) = (name.label.as_str(), &value) {
+                el.hoisted = true;
+
+                let target = el.typ;
+                let prop = format_args!(
+                    "::kobold::attribute::ElementRef::<::kobold::reexport::web_sys::{target}>::new()"
+                )
+                .tokenize();
+                let value = gen
+                    .add_field(expr.stream.clone())
+                    .node_ref(var, target, prop.clone())
+                    .name;
+
+                hydrate_attr(gen, var, value, &prop);
+
+                gen.add_hint(
+                    name.ident,
+                    format_args!("::kobold::dom::NodeRef<::kobold::reexport::web_sys::{target}>"),
+                );
+                continue;
+            }
+
             let attr_type = attribute_type(&name.label);
 
             match value {
                 AttributeValue::Literal(value) => {
                     let name = attribute_name(&name.label);
                     writeln!(el, "{var}.setAttribute(\"{name}\",{value});");
+                    write!(gen.out.html, "_h.attr(\"{name}\",{value});");
+                    let _ = write!(
+                        gen.out.hydrate,
+                        "_{var}.attr(\"{name}\",{});",
+                        str_lit(&value)
+                    );
+                }
+                AttributeValue::Boolean(value) if attr_type.is_boolean() => {
+                    if value.with_str(|v| v == "true") {
+                        writeln!(el, "{var}.setAttribute(\"{name}\",\"\");");
+                        gen.out.html.raw(&format!(" {name}"));
+                        let _ = write!(gen.out.hydrate, "_{var}.attr(\"{name}\",\"\");");
+                    }
+                }
+                AttributeValue::Boolean(value) if attr_type.takes_bool_string() => {
+                    let name = &name.label;
+                    writeln!(el, "{var}.setAttribute(\"{name}\",\"{value}\");");
+                    write!(gen.out.html, "_h.attr(\"{name}\",\"{value}\");");
+                    let _ = write!(gen.out.hydrate, "_{var}.attr(\"{name}\",\"{value}\");");
                 }
                 AttributeValue::Boolean(value) => {
                     writeln!(el, "{var}.{name}={value};");
+                    let _ = write!(gen.out.hydrate, "_{var}.prop(\"{name}\",{value});");
+
+                    if value.with_str(|v| v == "true") {
+                        gen.out.html.raw(&format!(" {name}"));
+                    }
                 }
                 AttributeValue::Expression(mut expr) => match &attr_type {
                     AttributeType::Event(event) => {
                         let target = el.typ;
+                        let annotated = closure_arg_type(&expr.stream);
 
-                        let coerce = if is_inline_closure(&mut expr.stream) {
+                        let coerce = if annotated.is_some() {
+                            // Event type is set explicitly, eg. for custom events
+                            expr.stream
+                        } else if is_inline_closure(&mut expr.stream) {
                             call(
                                 format_args!(
                                     "::kobold::internal::fn_type_hint::<\
@@ -124,13 +230,36 @@ impl IntoGenerator for HtmlElement {
                             (expr.stream, ".into_listener()").tokenize()
                         };
 
-                        let value = gen.add_field(coerce).event(event, el.typ).name;
+                        let event_type = annotated.unwrap_or_else(|| {
+                            format_args!(
+                                "::kobold::event::{event}<::kobold::reexport::web_sys::{target}>"
+                            )
+                            .tokenize()
+                        });
+
+                        let value = gen.add_field(coerce).event(event_type).name;
+
+                        let event = &name.label[2..];
 
                         writeln!(
                             el,
-                            "{var}.addEventListener(\"{}\",{value});",
-                            &name.label[2..]
+                            "{var}.addEventListener(\"{event}\",{});",
+                            js_listener(value, &options)
                         );
+                        let _ = write!(
+                            gen.out.hydrate,
+                            "let mut {value} = ::kobold::init!(_p.{value} @ self.{value}.build(_p));"
+                        );
+                        let _ = match options.flags() {
+                            0 => write!(
+                                gen.out.hydrate,
+                                "_{var}.listen(\"{event}\",&{value}.js_value());"
+                            ),
+                            flags => write!(
+                                gen.out.hydrate,
+                                "_{var}.listen_with(\"{event}\",&{value}.js_value(),{flags});"
+                            ),
+                        };
 
                         el.args.push(JsArgument::with_abi(value, InlineAbi::Event))
                     }
@@ -138,23 +267,38 @@ impl IntoGenerator for HtmlElement {
                         let name = attribute_name(&name.label);
                         el.hoisted = true;
 
+                        let prop = attr.prop();
                         let value = gen
                             .add_field(expr.stream)
-                            .attr(var, *attr, attr.prop())
+                            .attr(var, *attr, prop.clone())
                             .name;
 
+                        hydrate_attr(gen, var, value, &prop);
+
+                        if attr.name == "InnerHtml" {
+                            inner_html = Some((value, prop));
+                        } else {
+                            write!(gen.out.html, "self.{value}.render_html({prop},_h);");
+                        }
+
                         if let Some(abi) = attr.abi {
                             writeln!(el, "{var}.{name}={value};");
                             el.args.push(JsArgument::with_abi(value, abi))
                         }
                     }
-                    AttributeType::Unknown => {
+                    AttributeType::Named(attr) => {
                         el.hoisted = true;
 
                         let prop = (Literal::string(&name.label), ".into()").tokenize();
-                        let attr = Attr::new("&AttributeName");
+                        let attr = *attr;
+
+                        let value = gen
+                            .add_field(expr.stream)
+                            .attr(var, attr, prop.clone())
+                            .name;
 
-                        gen.add_field(expr.stream).attr(var, attr, prop);
+                        write!(gen.out.html, "self.{value}.render_html({prop},_h);");
+                        hydrate_attr(gen, var, value, &prop);
                     }
                 },
             };
@@ -177,17 +321,72 @@ impl IntoGenerator for HtmlElement {
                 AttributeType::Provided(attr) => {
                     gen.add_attr_hint(name.ident, "", attr.name);
                 }
-                AttributeType::Unknown => {
-                    gen.add_attr_hint(name.ident, "&'static", "AttributeName");
+                AttributeType::Named(attr) => {
+                    let (lt, attr_name) = attr.as_parts();
+
+                    gen.add_attr_hint(name.ident, lt, attr_name);
                 }
             }
         }
 
+        if !self.styles.is_empty() {
+            gen.out.html.raw(" style=\"");
+
+            for StyleProperty { name, value } in self.styles {
+                let property = &name.label;
+
+                match value {
+                    CssValue::Literal(value) => {
+                        writeln!(el, "{var}.style.setProperty(\"{property}\",{value});");
+                        write!(gen.out.html, "_h.style(\"{property}\",{value});");
+                        let _ = write!(
+                            gen.out.hydrate,
+                            "_{var}.style(\"{property}\",{});",
+                            str_lit(&value)
+                        );
+                    }
+                    CssValue::Expression(expr) => {
+                        el.hoisted = true;
+
+                        let prop = (Literal::string(property), ".into()").tokenize();
+                        let attr = Attr::new("&StyleProperty");
+
+                        let value = gen
+                            .add_field(expr.stream)
+                            .attr(var, attr, prop.clone())
+                            .name;
+
+                        write!(gen.out.html, "self.{value}.render_html({prop},_h);");
+                        hydrate_attr(gen, var, value, &prop);
+                    }
+                }
+
+                gen.add_attr_hint(name.ident, "&'static", "StyleProperty");
+            }
+
+            gen.out.html.raw("\"");
+        }
+
+        gen.out.html.raw(">");
+
+        if let Some((value, prop)) = inner_html {
+            write!(gen.out.html, "self.{value}.render_html({prop},_h);");
+        }
+
         if let Some(children) = self.children {
+            let parent = gen.parent.replace(var);
             let append = append(gen, &mut el.code, &mut el.args, children);
+            gen.parent = parent;
+
             writeln!(el, "{var}.{append};");
         }
 
+        let _ = write!(gen.out.hydrate, "_{var}.finish();");
+
+        if !el.tag.forbids_children() {
+            gen.out.html.raw(&format!("</{}>", el.tag));
+        }
+
         DomNode::Element(el)
     }
 }
@@ -221,7 +420,32 @@ impl InlineAbi {
 enum AttributeType {
     Provided(Attr),
     Event(&'static str),
-    Unknown,
+    /// Attributes set by name, such as `data-*` or ones unknown to the macro
+    Named(Attr),
+}
+
+impl AttributeType {
+    /// Boolean attributes are either present or absent, regardless of their value
+    fn is_boolean(&self) -> bool {
+        matches!(
+            self,
+            AttributeType::Named(Attr {
+                name: "&BooleanAttribute",
+                ..
+            })
+        )
+    }
+
+    /// `data-*` and `aria-*` attributes take `"true"` and `"false"` strings as values
+    fn takes_bool_string(&self) -> bool {
+        matches!(
+            self,
+            AttributeType::Named(Attr {
+                name: "&DataAttribute" | "&AriaAttribute",
+                ..
+            })
+        )
+    }
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -248,6 +472,45 @@ impl Attr {
     }
 }
 
+/// Set the attribute on the hydrated element, this also builds the memo for future updates.
+fn hydrate_attr(gen: &mut Generator, el: Short, name: Short, prop: &TokenStream) {
+    let _ = write!(
+        gen.out.hydrate,
+        "::kobold::init!(_p.{name} = self.{name}.build_in({prop}, _{el}.node()));"
+    );
+}
+
+/// Arguments to `addEventListener` following the event name, wrapping the `handler`
+/// to apply modifiers if necessary.
+fn js_listener(handler: Short, options: &ListenerOptions) -> String {
+    let mut js = String::new();
+
+    if options.prevent || options.stop {
+        js.push_str("(e)=>{");
+
+        if options.prevent {
+            js.push_str("e.preventDefault();");
+        }
+        if options.stop {
+            js.push_str("e.stopPropagation();");
+        }
+
+        let _ = write!(js, "{handler}(e)}}");
+    } else {
+        js.push_str(&handler);
+    }
+
+    if options.capture || options.passive || options.once {
+        let _ = write!(
+            js,
+            ",{{capture:{},passive:{},once:{}}}",
+            options.capture, options.passive, options.once
+        );
+    }
+
+    js
+}
+
 fn is_inline_closure(out: &mut TokenStream) -> bool {
     let mut is_closure = false;
     let mut stream = std::mem::replace(out, TokenStream::new()).parse_stream();
@@ -266,6 +529,27 @@ fn is_inline_closure(out: &mut TokenStream) -> bool {
     is_closure
 }
 
+/// Type annotation of the argument of an inline closure, such as `|e: Event<HtmlElement>| ...`
+fn closure_arg_type(expr: &TokenStream) -> Option<TokenStream> {
+    let mut stream = expr.clone().parse_stream();
+
+    stream.allow_consume("move");
+    stream.allow_consume('|')?;
+    stream.next()?;
+    stream.allow_consume(':')?;
+
+    let mut typ = TokenStream::new();
+
+    for tt in stream {
+        if tt.is('|') {
+            return Some(typ);
+        }
+        typ.write(tt);
+    }
+
+    None
+}
+
 fn attribute_name(attr: &str) -> &str {
     match attr {
         "html" => "innerHTML",
@@ -286,21 +570,35 @@ fn attribute_type(attr: &str) -> AttributeType {
         },
         "href" => Attr {
             name: "Href",
-            abi: Some(InlineAbi::Str),
+            abi: None,
         },
         "html" => Attr {
             name: "InnerHtml",
             abi: Some(InlineAbi::Str),
         },
+        "muted" => Attr {
+            name: "Muted",
+            abi: Some(InlineAbi::Bool),
+        },
+        "selected" => Attr {
+            name: "Selected",
+            abi: Some(InlineAbi::Bool),
+        },
         "style" => Attr {
             name: "Style",
-            abi: Some(InlineAbi::Str),
+            abi: None,
         },
         "value" => Attr {
             name: "Value",
             abi: None,
         },
-        _ => return AttributeType::Unknown,
+        "allowfullscreen" | "async" | "autofocus" | "autoplay" | "controls" | "default"
+        | "defer" | "disabled" | "formnovalidate" | "hidden" | "inert" | "ismap" | "loop"
+        | "multiple" | "nomodule" | "novalidate" | "open" | "playsinline" | "readonly"
+        | "required" | "reversed" => return AttributeType::Named(Attr::new("&BooleanAttribute")),
+        _ if attr.starts_with("data-") => return AttributeType::Named(Attr::new("&DataAttribute")),
+        _ if attr.starts_with("aria-") => return AttributeType::Named(Attr::new("&AriaAttribute")),
+        _ => return AttributeType::Named(Attr::new("&AttributeName")),
     };
 
     AttributeType::Provided(attr)
@@ -322,6 +620,57 @@ fn event_js_type(event: &str) -> &'static str {
         "keydown"
         | "keyup"
         | "keypress" => "KeyboardEvent",
+
+        "input"
+        | "beforeinput" => "InputEvent",
+
+        "focus"
+        | "blur"
+        | "focusin"
+        | "focusout" => "FocusEvent",
+
+        "pointerdown"
+        | "pointerup"
+        | "pointermove"
+        | "pointerover"
+        | "pointerout"
+        | "pointerenter"
+        | "pointerleave"
+        | "pointercancel"
+        | "gotpointercapture"
+        | "lostpointercapture" => "PointerEvent",
+
+        "wheel" => "WheelEvent",
+
+        "drag"
+        | "dragstart"
+        | "dragend"
+        | "dragenter"
+        | "dragleave"
+        | "dragover"
+        | "drop" => "DragEvent",
+
+        "touchstart"
+        | "touchend"
+        | "touchmove"
+        | "touchcancel" => "TouchEvent",
+
+        "submit" => "SubmitEvent",
+
+        "animationstart"
+        | "animationend"
+        | "animationiteration"
+        | "animationcancel" => "AnimationEvent",
+
+        "transitionstart"
+        | "transitionend"
+        | "transitionrun"
+        | "transitioncancel" => "TransitionEvent",
+
+        "copy"
+        | "cut"
+        | "paste" => "ClipboardEvent",
+
         _ => "Event",
     }
 }
@@ -27,7 +27,15 @@ impl IntoGenerator for Vec<Node> {
         let mut code = format!("let {var}=document.createDocumentFragment();\n");
         let mut args = Vec::new();
 
+        let _ = write!(
+            gen.out.hydrate,
+            "let mut _{var} = {}.fragment();",
+            gen.cursor()
+        );
+
+        let parent = gen.parent.replace(var);
         let append = append(gen, &mut code, &mut args, self);
+        gen.parent = parent;
         let _ = writeln!(code, "{var}.{append};");
         let _ = writeln!(code, "return {var};");
 
@@ -0,0 +1,41 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::fmt::{Arguments, Write};
+
+/// Rust code rendering a view into `::kobold::ssr::Html` available as `_h`.
+#[derive(Default, Debug)]
+pub struct HtmlCode {
+    code: String,
+
+    /// Markup not yet written to `code`, consecutive chunks are merged into a single `_h.raw` call
+    raw: String,
+}
+
+impl HtmlCode {
+    /// Append raw markup, such as `<div` or `>`.
+    pub fn raw(&mut self, markup: &str) {
+        self.raw.push_str(markup);
+    }
+
+    /// Append a Rust statement, such as `_h.text("foo");`
+    pub fn write_fmt(&mut self, args: Arguments) {
+        self.flush();
+
+        let _ = self.code.write_fmt(args);
+    }
+
+    fn flush(&mut self) {
+        if !self.raw.is_empty() {
+            let _ = write!(self.code, "_h.raw({:?});", self.raw);
+
+            self.raw.clear();
+        }
+    }
+
+    pub fn finish(mut self) -> String {
+        self.flush();
+        self.code
+    }
+}
@@ -8,7 +8,7 @@ use arrayvec::ArrayString;
 use tokens::{Ident, Literal, TokenStream};
 
 use crate::gen::element::{Attr, InlineAbi};
-use crate::gen::Short;
+use crate::gen::{HtmlCode, Short};
 use crate::itertools::IteratorExt;
 use crate::parse::IdentExt;
 use crate::tokenize::prelude::*;
@@ -22,6 +22,8 @@ pub struct Transient {
     pub hints: Vec<Hint>,
     pub fields: Vec<Field>,
     pub els: Vec<Short>,
+    pub html: HtmlCode,
+    pub hydrate: String,
 }
 
 #[derive(Debug)]
@@ -46,11 +48,22 @@ impl Transient {
 
     fn tokenize_const(self, stream: &mut TokenStream) {
         let JsFunction { name, .. } = self.js.functions[0];
+        let html = self.html.finish();
+        let hydrate = self.hydrate;
+        let el = self.els[0];
 
         block((
             "use ::kobold::reexport::wasm_bindgen;",
             self.js,
-            format_args!("::kobold::internal::Precompiled({name})"),
+            format_args!(
+                "::kobold::internal::Precompiled({name}).with_html(\
+                    |_h: &mut ::kobold::ssr::Html| {{ {html} }},\
+                    |_c: &mut ::kobold::hydrate::Cursor| {{\
+                        {hydrate}\
+                        ::kobold::reexport::web_sys::Node::from(_{el})\
+                    }},\
+                )"
+            ),
         ))
         .tokenize_in(stream)
     }
@@ -148,6 +161,7 @@ impl Tokenize for Transient {
         }
 
         let mut declare_els = String::new();
+        let mut hydrate = self.hydrate;
 
         for (jsfn, el) in self.js.functions.iter().zip(self.els) {
             let JsFunction { name, anchor, args } = jsfn;
@@ -171,12 +185,18 @@ impl Tokenize for Transient {
                 build,
                 "let {el} = ::kobold::init!(_p.{el} = {anchor_type}::from({name}({args})));"
             );
+            let _ = write!(
+                hydrate,
+                "::kobold::init!(_p.{el} = {anchor_type}::from(_{el}));"
+            );
         }
         let anchor = &self.js.functions.last().unwrap().anchor;
 
         let anchor_type = anchor.as_type();
         let anchor_js_type = anchor.as_js_type();
 
+        let html = self.html.finish();
+
         block((
             (
                 "\
@@ -229,6 +249,22 @@ impl Tokenize for Transient {
                     fn update(self, p: &mut Self::Product) {{\
                         {update}\
                     }}\
+                    \
+                    fn hydrate<'p>(\
+                        self,\
+                        _p: ::kobold::internal::In<'p, Self::Product>,\
+                        _c: &mut ::kobold::hydrate::Cursor,\
+                    ) -> ::kobold::internal::Out<'p, Self::Product> {{\
+                        _p.in_place(move |_p| unsafe {{\
+                            {hydrate}\
+                            \
+                            ::kobold::internal::Out::from_raw(_p)\
+                        }})\
+                    }}\
+                    \
+                    fn render_html(self, _h: &mut ::kobold::ssr::Html) {{\
+                        {html}\
+                    }}\
                 }}\
                 \
                 "
@@ -278,17 +314,6 @@ impl Debug for JsModule {
     }
 }
 
-pub struct JsAttrConstructor(pub JsFnName);
-
-impl Tokenize for JsAttrConstructor {
-    fn tokenize_in(self, stream: &mut TokenStream) {
-        let name = self.0;
-        stream.write(format_args!(
-            "fn {name}() -> ::kobold::reexport::web_sys::Node;"
-        ));
-    }
-}
-
 #[derive(Debug)]
 pub struct JsFunction {
     pub name: JsFnName,
@@ -383,14 +408,18 @@ pub enum FieldKind {
     StaticView,
     View,
     Event {
-        event: &'static str,
-        target: &'static str,
+        event: TokenStream,
     },
     Attribute {
         el: Short,
         attr: Attr,
         prop: TokenStream,
     },
+    NodeRef {
+        el: Short,
+        target: &'static str,
+        prop: TokenStream,
+    },
 }
 
 impl Debug for Field {
@@ -404,12 +433,15 @@ impl Debug for Field {
             FieldKind::View => {
                 write!(f, "{name} <View>: {value}")
             }
-            FieldKind::Event { event, target } => {
-                write!(f, "{name} <Listener<{event}<{target}>>>: {value}")
+            FieldKind::Event { event } => {
+                write!(f, "{name} <Listener<{event}>>: {value}")
             }
             FieldKind::Attribute { attr, .. } => {
                 write!(f, "{name} <Attribute<{}>>: {value}", attr.name)
             }
+            FieldKind::NodeRef { target, .. } => {
+                write!(f, "{name} <NodeRef<{target}>>: {value}")
+            }
         }
     }
 }
@@ -423,8 +455,8 @@ impl Field {
         }
     }
 
-    pub fn event(&mut self, event: &'static str, target: &'static str) -> &mut Self {
-        self.kind = FieldKind::Event { event, target };
+    pub fn event(&mut self, event: TokenStream) -> &mut Self {
+        self.kind = FieldKind::Event { event };
         self
     }
 
@@ -433,6 +465,11 @@ impl Field {
         self
     }
 
+    pub fn node_ref(&mut self, el: Short, target: &'static str, prop: TokenStream) -> &mut Self {
+        self.kind = FieldKind::NodeRef { el, target, prop };
+        self
+    }
+
     fn name_value(&self) -> (&Short, &TokenStream) {
         (&self.name, &self.value)
     }
@@ -455,13 +492,11 @@ impl Field {
             FieldKind::View | FieldKind::StaticView => {
                 buf.write((typ.as_str(), ": ::kobold::View,"));
             }
-            FieldKind::Event { event, target } => {
-                buf.write(format_args!(
-                    "{typ}: ::kobold::event::Listener<\
-                        ::kobold::event::{event}<\
-                            ::kobold::reexport::web_sys::{target}\
-                        >
-                    >,"
+            FieldKind::Event { event } => {
+                buf.write((
+                    format_args!("{typ}: ::kobold::event::Listener<"),
+                    event.clone(),
+                    ">,",
                 ));
             }
             FieldKind::Attribute { attr, .. } => {
@@ -474,6 +509,15 @@ impl Field {
                     ',',
                 ));
             }
+            FieldKind::NodeRef { target, .. } => {
+                buf.write(format_args!(
+                    "{typ}: ::kobold::attribute::Attribute<\
+                        ::kobold::attribute::ElementRef<\
+                            ::kobold::reexport::web_sys::{target}\
+                        >\
+                    >,"
+                ));
+            }
         }
     }
 
@@ -512,7 +556,7 @@ impl Field {
             FieldKind::Attribute { attr, .. } if attr.abi.is_some() => {
                 let _ = write!(post, "::kobold::init!(_p.{name} = self.{name}.build());");
             }
-            FieldKind::Attribute { el, prop, .. } => {
+            FieldKind::Attribute { el, prop, .. } | FieldKind::NodeRef { el, prop, .. } => {
                 let _ = write!(
                     post,
                     "::kobold::init!(_p.{name} = self.{name}.build_in({prop}, &{el}));"
@@ -529,7 +573,7 @@ impl Field {
             FieldKind::View | FieldKind::Event { .. } => {
                 let _ = write!(buf, "self.{name}.update(&mut p.{name});");
             }
-            FieldKind::Attribute { el, prop, .. } => {
+            FieldKind::Attribute { el, prop, .. } | FieldKind::NodeRef { el, prop, .. } => {
                 let _ = write!(
                     buf,
                     "self.{name}.update_in({prop}, &p.{el}, &mut p.{name});"
@@ -17,6 +17,7 @@ use arrayvec::ArrayString;
 
 mod branching;
 mod class;
+mod css;
 mod dom;
 mod fn_component;
 mod gen;
@@ -36,7 +37,7 @@ macro_rules! unwrap_err {
     };
 }
 
-#[allow(clippy::let_and_return)]
+#[allow(clippy::let_and_return, clippy::useless_conversion)]
 #[proc_macro_attribute]
 pub fn component(args: TokenStream, input: TokenStream) -> TokenStream {
     let args = unwrap_err!(fn_component::args(args.into()));
@@ -48,7 +49,7 @@ pub fn component(args: TokenStream, input: TokenStream) -> TokenStream {
     out.into()
 }
 
-#[allow(clippy::let_and_return)]
+#[allow(clippy::let_and_return, clippy::useless_conversion)]
 #[proc_macro]
 pub fn view(body: TokenStream) -> TokenStream {
     let nodes = unwrap_err!(dom::parse(body.into()));
@@ -64,7 +65,7 @@ pub fn view(body: TokenStream) -> TokenStream {
     out.into()
 }
 
-#[allow(clippy::let_and_return)]
+#[allow(clippy::let_and_return, clippy::useless_conversion)]
 #[proc_macro]
 pub fn class(stream: TokenStream) -> TokenStream {
     let out = unwrap_err!(class::parse(stream.into()));
@@ -72,6 +73,14 @@ pub fn class(stream: TokenStream) -> TokenStream {
     out.into()
 }
 
+#[allow(clippy::let_and_return, clippy::useless_conversion)]
+#[proc_macro]
+pub fn css(stream: TokenStream) -> TokenStream {
+    let out = unwrap_err!(css::parse(stream.into()));
+
+    out.into()
+}
+
 fn unique() -> ArrayString<8> {
     use std::sync::atomic::{AtomicUsize, Ordering};
 
@@ -10,12 +10,14 @@ use kobold::diff::fence;
 use web_sys::CanvasRenderingContext2d;
 
 /// Error Correction Coding has 4 levels
+#[derive(Default)]
 pub enum Ecl {
     /// Low, 7%
     L,
     /// Medium, 15%
     M,
     /// Quartile, 25%
+    #[default]
     Q,
     /// High, 30%
     H,
@@ -32,12 +34,6 @@ impl From<Ecl> for fast_qr::ECL {
     }
 }
 
-impl Default for Ecl {
-    fn default() -> Self {
-        Ecl::Q
-    }
-}
-
 #[component(
     size?: 200,
     ecl?,
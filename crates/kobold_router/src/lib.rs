@@ -33,6 +33,12 @@ pub enum ParamError {
     CouldNotParseParam,
 }
 
+impl Default for Router {
+    fn default() -> Self {
+        Router::new()
+    }
+}
+
 /// Implement of [Router]
 impl Router {
     pub fn new() -> Self {
@@ -144,12 +150,7 @@ pub fn link<'a>(route: &'a str, class: &'a str, children: impl View + 'a) -> imp
     // TODO Not sure if clone is the best solution, but also need the route for the href tag for browser decoration
     let href = route.clone();
     //TODO work on implmenting a fence for event listeners
-    let onclick = move |event: MouseEvent<_>| {
-        navigate(&route);
-        event.prevent_default();
-    };
-
     view! {
-        <a href={href} {class} {onclick}>{children}</a>
+        <a href={href} {class} onclick.prevent={move |_| navigate(&route)}>{children}</a>
     }
 }
@@ -1,3 +1,4 @@
+use kobold::event::FocusEvent;
 use kobold::prelude::*;
 use web_sys::HtmlInputElement as InputElement;
 
@@ -89,7 +90,7 @@ fn entry<'a>(idx: usize, entry: &'a Entry, state: &'a Hook<State>) -> impl View
                 Then::Stop
             }
         });
-        let onblur = event!(move |state, e: Event<InputElement>| {
+        let onblur = event!(move |state, e: FocusEvent<InputElement>| {
             state.update(idx, e.current_target().value());
         });
 